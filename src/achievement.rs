@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use crate::task::TaskEvent;
+use crate::tile::CropType;
+use crate::i18n::t;
+
+// 表示成就的达成条件
+pub enum Condition {
+    Harvested(Option<CropType>, u32), // 收获指定作物（None 表示任意作物）的数量
+    CoinsEarned(u64),
+    PestsCleared(u32),
+    GoldenCrops(u32),
+    DayStreak(u32), // 连续游玩的天数
+}
+
+// 表示一个成就
+pub struct AchievementDef {
+    pub id: &'static str,
+    pub icon: &'static str,
+    pub condition: Condition,
+}
+
+// 所有成就
+pub const ACHIEVEMENTS: &[AchievementDef] = &[
+    AchievementDef { id: "first_harvest", icon: "🌾", condition: Condition::Harvested(None, 1) },
+    AchievementDef { id: "seasoned_farmer", icon: "🧑‍🌾", condition: Condition::Harvested(None, 500) },
+    AchievementDef { id: "wheat_king", icon: "🍞", condition: Condition::Harvested(Some(CropType::Wheat), 100) },
+    AchievementDef { id: "corn_master", icon: "🌽", condition: Condition::Harvested(Some(CropType::Corn), 50) },
+    AchievementDef { id: "carrot_lover", icon: "🥕", condition: Condition::Harvested(Some(CropType::Carrot), 50) },
+    AchievementDef { id: "golden_touch", icon: "✨", condition: Condition::GoldenCrops(1) },
+    AchievementDef { id: "golden_age", icon: "👑", condition: Condition::GoldenCrops(25) },
+    AchievementDef { id: "savings", icon: "💰", condition: Condition::CoinsEarned(1000) },
+    AchievementDef { id: "tycoon", icon: "🏦", condition: Condition::CoinsEarned(10000) },
+    AchievementDef { id: "pest_hunter", icon: "🐛", condition: Condition::PestsCleared(20) },
+    AchievementDef { id: "regular", icon: "📅", condition: Condition::DayStreak(3) },
+    AchievementDef { id: "dedicated", icon: "🏅", condition: Condition::DayStreak(7) },
+];
+
+pub fn find_achievement(id: &str) -> Option<&'static AchievementDef> {
+    ACHIEVEMENTS.iter().find(|a| a.id == id)
+}
+
+impl AchievementDef {
+    pub fn name(&self) -> &'static str {
+        t(&format!("achievement.{}.name", self.id))
+    }
+
+    pub fn description(&self) -> &'static str {
+        t(&format!("achievement.{}.description", self.id))
+    }
+
+    fn view(&self, unlocked: bool) -> AchievementView {
+        AchievementView {
+            id: self.id.to_string(),
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            icon: self.icon.to_string(),
+            unlocked,
+        }
+    }
+}
+
+// 跨存档累计的统计数据
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LifetimeStats {
+    pub harvested: HashMap<CropType, u32>, // 每种作物的累计收获数量
+    pub coins_earned: u64,
+    pub pests_cleared: u32,
+    pub golden_crops: u32,
+    pub day_streak: u32,                 // 当前连续游玩的天数
+    pub last_played_day: Option<u64>,    // 上次游玩的日期（自 1970 年起的天数）
+}
+
+impl LifetimeStats {
+    // 与之前相比增加的计数，连续游玩天数不随操作变化，不计入其中
+    pub fn difference(&self, before: &LifetimeStats) -> LifetimeStats {
+        let harvested = self
+            .harvested
+            .iter()
+            .map(|(crop, count)| (*crop, count.saturating_sub(before.harvested.get(crop).copied().unwrap_or(0))))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        LifetimeStats {
+            harvested,
+            coins_earned: self.coins_earned.saturating_sub(before.coins_earned),
+            pests_cleared: self.pests_cleared.saturating_sub(before.pests_cleared),
+            golden_crops: self.golden_crops.saturating_sub(before.golden_crops),
+            ..LifetimeStats::default()
+        }
+    }
+
+    // 扣除撤销的操作带来的计数
+    pub fn subtract(&mut self, delta: &LifetimeStats) {
+        for (crop, count) in &delta.harvested {
+            if let Some(total) = self.harvested.get_mut(crop) {
+                *total = total.saturating_sub(*count);
+                if *total == 0 {
+                    self.harvested.remove(crop);
+                }
+            }
+        }
+        self.coins_earned = self.coins_earned.saturating_sub(delta.coins_earned);
+        self.pests_cleared = self.pests_cleared.saturating_sub(delta.pests_cleared);
+        self.golden_crops = self.golden_crops.saturating_sub(delta.golden_crops);
+    }
+
+    fn total_harvested(&self) -> u32 {
+        self.harvested.values().sum()
+    }
+
+    fn is_met(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Harvested(None, count) => self.total_harvested() >= *count,
+            Condition::Harvested(Some(crop), count) => self.harvested.get(crop).copied().unwrap_or(0) >= *count,
+            Condition::CoinsEarned(amount) => self.coins_earned >= *amount,
+            Condition::PestsCleared(count) => self.pests_cleared >= *count,
+            Condition::GoldenCrops(count) => self.golden_crops >= *count,
+            Condition::DayStreak(days) => self.day_streak >= *days,
+        }
+    }
+}
+
+// 成就进度，与游戏存档分开保存
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AchievementTracker {
+    pub stats: LifetimeStats,
+    pub unlocked: Vec<String>,
+    #[serde(skip)]
+    pending: Vec<String>, // 新解锁、尚未通知界面的成就
+}
+
+// 提供给界面的成就信息
+#[derive(Serialize)]
+pub struct AchievementView {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub unlocked: bool,
+}
+
+impl AchievementTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_event(&mut self, event: &TaskEvent) {
+        match event {
+            TaskEvent::Harvested(crop) => {
+                *self.stats.harvested.entry(*crop).or_insert(0) += 1;
+                if crop.tier() == 2 {
+                    self.stats.golden_crops += 1;
+                }
+            }
+            TaskEvent::Earned(amount) => self.stats.coins_earned += *amount as u64,
+            TaskEvent::PestCleared => self.stats.pests_cleared += 1,
+            _ => return,
+        }
+        self.check();
+    }
+
+    // 记录游玩日期，连续的日期累加连续天数
+    pub fn record_play_day(&mut self, day: u64) {
+        match self.stats.last_played_day {
+            Some(last) if last == day => return,
+            Some(last) if last + 1 == day => self.stats.day_streak += 1,
+            _ => self.stats.day_streak = 1,
+        }
+        self.stats.last_played_day = Some(day);
+        self.check();
+    }
+
+    // 解锁所有已满足条件的成就
+    fn check(&mut self) {
+        for achievement in ACHIEVEMENTS {
+            let unlocked = self.unlocked.iter().any(|id| id == achievement.id);
+            if !unlocked && self.stats.is_met(&achievement.condition) {
+                self.unlocked.push(achievement.id.to_string());
+                self.pending.push(achievement.id.to_string());
+            }
+        }
+    }
+
+    // 取出新解锁的成就，供界面弹出提示
+    pub fn take_pending(&mut self) -> Vec<AchievementView> {
+        self.pending
+            .drain(..)
+            .filter_map(|id| find_achievement(&id))
+            .map(|a| a.view(true))
+            .collect()
+    }
+
+    pub fn views(&self) -> Vec<AchievementView> {
+        ACHIEVEMENTS
+            .iter()
+            .map(|a| a.view(self.unlocked.iter().any(|id| id == a.id)))
+            .collect()
+    }
+}
@@ -0,0 +1,234 @@
+// 通过导出函数执行玩家操作，检查撤销记录与任务、统计进度之间的关系，以及操作日志的回放和存档读取
+use super::*;
+use crate::fuzz_tests::headless_game;
+
+fn task_progress(task_id: u32) -> u32 {
+    TASKS.with(|tasks| tasks.borrow().iter().find(|t| t.id == task_id).map_or(0, |t| t.progress))
+}
+
+fn planted_total() -> u32 {
+    STATS.with(|stats| stats.borrow().totals.crops.get(&CropType::Wheat).map_or(0, |c| c.planted))
+}
+
+#[test]
+fn undo_reverts_a_plant() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    let balance = get_balance();
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    assert!(task_progress(1) == 1 && planted_total() == 1);
+
+    assert!(undo_last_action().is_ok());
+    assert!(tile_state(0, 0) == Ok("empty".to_string()));
+    assert!(FARM.with(|farm| farm.borrow().inventory.seeds.get(&ItemId::Crop(CropType::Wheat)).copied()) == Some(1));
+    assert!(get_balance() == balance);
+    assert!(task_progress(1) == 0 && planted_total() == 0);
+
+    // 撤销买种子后没有更早的记录
+    assert!(undo_last_action().is_ok());
+    assert!(execute(Command::Undo).err() == Some(GameError::NothingToUndo));
+}
+
+#[test]
+fn undo_keeps_progress_made_after_the_action() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    // 自动化规则等不可撤销的途径在操作之后推进的进度
+    record_task_event(TaskEvent::Planted(CropType::Wheat));
+    record_stat(StatEvent::Planted(CropType::Wheat));
+    assert!(task_progress(1) == 2 && planted_total() == 2);
+
+    assert!(undo_last_action().is_ok());
+    assert!(task_progress(1) == 1 && planted_total() == 1);
+}
+
+#[test]
+fn undo_keeps_growth_made_after_fertilizing() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    assert!(buy_fertilizer("basic_fertilizer".to_string()).is_ok());
+    assert!(fertilize(0, 0).is_ok());
+    for _ in 0..3 {
+        FARM.with(|farm| farm.borrow_mut().tick_without_infestation());
+    }
+
+    assert!(undo_last_action().is_ok());
+    let state = FARM.with(|farm| farm.borrow().grid[0][0].state);
+    assert!(matches!(state, TileState::Planted { timer: 3, fertilizer: FertilizerType::None, .. }));
+}
+
+#[test]
+fn failed_undo_keeps_the_entry() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    for _ in 0..40 {
+        FARM.with(|farm| farm.borrow_mut().tick_without_infestation());
+    }
+    assert!(harvest(0, 0).is_ok());
+    assert!(sell_crop("wheat".to_string()).is_ok());
+    let balance = get_balance();
+
+    // 卖出得到的金币已经花掉时不能撤销，之后凑够金币仍可撤销同一次出售
+    SHOP.with(|shop| shop.borrow_mut().balance = 0);
+    assert!(matches!(execute(Command::Undo).err(), Some(GameError::InsufficientFunds { .. })));
+    SHOP.with(|shop| shop.borrow_mut().balance = balance);
+    assert!(undo_last_action().is_ok());
+    assert!(FARM.with(|farm| farm.borrow().inventory.crop_count(CropType::Wheat)) == 1);
+}
+
+#[test]
+fn undo_expires_after_the_window() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    for _ in 0..=undo::UNDO_WINDOW_TICKS {
+        FARM.with(|farm| farm.borrow_mut().tick_without_infestation());
+    }
+    assert!(execute(Command::Undo).err() == Some(GameError::UndoExpired));
+}
+
+#[test]
+fn claiming_a_reward_clears_the_undo_history() {
+    headless_game();
+    for col in 0..3 {
+        assert!(buy_seed("carrot".to_string()).is_ok());
+        assert!(plant(0, col, "carrot".to_string()).is_ok());
+    }
+    assert!(TASKS.with(|tasks| tasks.borrow().iter().any(|t| t.id == 3 && t.completed)));
+    assert!(claim_task_reward(3).is_ok());
+    let balance = get_balance();
+
+    // 撤销种植不能让任务回到未领取状态，避免重复领取
+    assert!(execute(Command::Undo).err() == Some(GameError::NothingToUndo));
+    assert!(tile_state(0, 2) == Ok("planted_carrot".to_string()));
+    assert!(execute(Command::ClaimTaskReward { task_id: 3 }).is_err());
+    assert!(get_balance() == balance);
+}
+
+#[test]
+fn replaying_the_log_rebuilds_the_same_state() {
+    crate::utils::set_muted(true);
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    reset_game(42);
+    for col in 0..2 {
+        assert!(buy_seed("wheat".to_string()).is_ok());
+        assert!(plant(0, col, "wheat".to_string()).is_ok());
+    }
+    assert!(buy_fertilizer("basic_fertilizer".to_string()).is_ok());
+    assert!(select_fertilizer("premium_fertilizer".to_string()).is_ok());
+    // 模拟重新载入页面后选择回到默认肥料，施肥操作需自带肥料才能按原样回放
+    SELECTED_FERTILIZER.with(|f| *f.borrow_mut() = default_fertilizer());
+    assert!(fertilize(0, 0).is_ok());
+    for _ in 0..40 {
+        tick();
+    }
+    assert!(harvest(0, 0).is_ok());
+    assert!(harvest(0, 1).is_ok());
+    assert!(sell_crop("wheat".to_string()).is_ok());
+    for _ in 0..25 {
+        tick();
+    }
+
+    let expected = serde_json::to_string(&game_state()).unwrap();
+    let mut log = ACTION_LOG.with(|log| log.borrow().clone());
+    log.end_tick = FARM.with(|farm| farm.borrow().ticks);
+    assert!(replay(&log).is_ok());
+    crate::utils::set_muted(true);
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    assert!(serde_json::to_string(&game_state()).unwrap() == expected);
+}
+
+#[test]
+fn replaying_from_a_checkpoint_rebuilds_the_same_state() {
+    crate::utils::set_muted(true);
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    reset_game(42);
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    // 切换肥料不改变农场，用来让日志达到上限
+    for i in 0..command::MAX_LOGGED_COMMANDS {
+        let fertilizer = if i % 2 == 0 { "premium_fertilizer" } else { "basic_fertilizer" };
+        assert!(select_fertilizer(fertilizer.to_string()).is_ok());
+    }
+    for _ in 0..40 {
+        tick();
+    }
+    assert!(harvest(0, 0).is_ok());
+    assert!(sell_crop("wheat".to_string()).is_ok());
+
+    let expected = serde_json::to_string(&game_state()).unwrap();
+    let mut log = ACTION_LOG.with(|log| log.borrow().clone());
+    assert!(log.checkpoint.is_some() && log.entries.len() < command::MAX_LOGGED_COMMANDS);
+    log.end_tick = FARM.with(|farm| farm.borrow().ticks);
+    assert!(replay(&log).is_ok());
+    crate::utils::set_muted(true);
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    assert!(serde_json::to_string(&game_state()).unwrap() == expected);
+}
+
+#[test]
+fn loading_skips_unknown_items() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    let mut state = serde_json::to_value(game_state()).unwrap();
+    state["inventory_seeds"]["removed_crop"] = serde_json::json!(3);
+    let mut task = state["tasks"][0].clone();
+    task["task_type"] = serde_json::json!({ "OwnItem": { "item": "removed_crop", "count": 1 } });
+    state["tasks"].as_array_mut().unwrap().push(task);
+    let tasks = state["tasks"].as_array().unwrap().len();
+
+    let Ok(loaded) = serde_json::from_value::<GameState>(state) else {
+        panic!("存档因未知物品读取失败");
+    };
+    assert!(loaded.inventory_seeds.len() == 1);
+    assert!(loaded.inventory_seeds.contains_key(&ItemId::Crop(CropType::Wheat)));
+    assert!(loaded.tasks.len() == tasks - 1);
+}
+
+#[test]
+fn loading_checks_ids_in_rewards_and_codex() {
+    headless_game();
+    let mut state = serde_json::to_value(game_state()).unwrap();
+    // 旧存档按枚举名称保存地块上的作物和肥料
+    state["farm_grid"][0][0] = serde_json::json!({ "Planted": { "crop": "Wheat", "timer": 3, "fertilizer": "Basic" } });
+    let mut task = state["tasks"][0].clone();
+    task["extras"] = serde_json::json!([{ "Seed": { "id": "removed_crop", "count": 2 } }]);
+    state["tasks"].as_array_mut().unwrap().push(task);
+    let tasks = state["tasks"].as_array().unwrap().len();
+
+    let Ok(loaded) = serde_json::from_value::<GameState>(state.clone()) else {
+        panic!("旧版存档读取失败");
+    };
+    assert!(loaded.farm_grid[0][0].crop() == Some(CropType::Wheat));
+    assert!(matches!(loaded.farm_grid[0][0], TileState::Planted { fertilizer: FertilizerType::Basic, .. }));
+    assert!(loaded.tasks.len() == tasks - 1);
+    assert!(serde_json::to_value(loaded.farm_grid[0][0]).unwrap()["Planted"]["crop"] == "wheat");
+
+    state["codex"] = serde_json::json!({ "discovered": ["wheat", "removed_crop"] });
+    assert!(serde_json::from_value::<GameState>(state).is_err());
+}
+
+#[test]
+fn area_actions_keep_the_undo_history_when_nothing_changes() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    // 没有成熟的作物，也没有种子，两个区域操作都不改变农场
+    assert!(harvest_all_action().succeeded == 0);
+    let Ok(summary) = plant_rect_action(0, 1, 0, 3, "wheat".to_string()) else {
+        panic!("区域种植失败");
+    };
+    assert!(summary.succeeded == 0);
+    assert!(undo_last_action().is_ok());
+    assert!(tile_state(0, 0) == Ok("empty".to_string()));
+
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    let Ok(summary) = plant_rect_action(0, 1, 0, 3, "wheat".to_string()) else {
+        panic!("区域种植失败");
+    };
+    assert!(summary.succeeded == 2);
+    assert!(execute(Command::Undo).is_err());
+}
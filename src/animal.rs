@@ -0,0 +1,215 @@
+use serde::{Serialize, Deserialize};
+use crate::processing::Good;
+use crate::tile::CropType;
+use crate::i18n::{self, tf};
+
+// 每个围栏最多容纳的动物数量
+pub const PEN_CAPACITY: usize = 4;
+// 饥饿度上限
+pub const MAX_HUNGER: u32 = 100;
+// 饥饿度达到该值后动物停止产出，心情开始下降
+pub const HUNGRY_THRESHOLD: u32 = 70;
+// 每次喂食降低的饥饿度
+pub const FEED_AMOUNT: u32 = 50;
+// 心情达到该值时产出翻倍
+pub const HAPPY_THRESHOLD: u32 = 80;
+
+// 动物产品及其售价
+pub const PRODUCTS: &[Good] = &[
+    Good { id: "egg", price: 20 },
+    Good { id: "milk", price: 55 },
+    Good { id: "wool", price: 80 },
+];
+
+// 表示动物类型
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnimalType {
+    Chicken,
+    Cow,
+    Sheep,
+}
+
+impl AnimalType {
+    pub fn id(&self) -> &'static str {
+        match self {
+            AnimalType::Chicken => "chicken",
+            AnimalType::Cow => "cow",
+            AnimalType::Sheep => "sheep",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<AnimalType> {
+        match id {
+            "chicken" => Some(AnimalType::Chicken),
+            "cow" => Some(AnimalType::Cow),
+            "sheep" => Some(AnimalType::Sheep),
+            _ => None,
+        }
+    }
+
+    // 围栏在库存和商店中使用的标识
+    pub fn pen_id(&self) -> &'static str {
+        match self {
+            AnimalType::Chicken => "chicken_coop",
+            AnimalType::Cow => "cow_barn",
+            AnimalType::Sheep => "sheep_pen",
+        }
+    }
+
+    pub fn from_pen_id(id: &str) -> Option<AnimalType> {
+        match id {
+            "chicken_coop" => Some(AnimalType::Chicken),
+            "cow_barn" => Some(AnimalType::Cow),
+            "sheep_pen" => Some(AnimalType::Sheep),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        i18n::t(&format!("animal.{}.name", self.id()))
+    }
+
+    pub fn pen_display_name(&self) -> &'static str {
+        i18n::t(&format!("pen.{}.name", self.pen_id()))
+    }
+
+    // 商店中显示的围栏说明
+    pub fn pen_description(&self) -> &'static str {
+        i18n::t(&format!("pen.{}.description", self.pen_id()))
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            AnimalType::Chicken => "🐔",
+            AnimalType::Cow => "🐄",
+            AnimalType::Sheep => "🐑",
+        }
+    }
+
+    // 可以喂食的作物，按优先顺序排列
+    pub fn feed(&self) -> &'static [CropType] {
+        match self {
+            AnimalType::Chicken => &[CropType::Corn, CropType::Wheat],
+            AnimalType::Cow => &[CropType::Wheat, CropType::Corn],
+            AnimalType::Sheep => &[CropType::Wheat],
+        }
+    }
+
+    pub fn product(&self) -> &'static str {
+        match self {
+            AnimalType::Chicken => "egg",
+            AnimalType::Cow => "milk",
+            AnimalType::Sheep => "wool",
+        }
+    }
+
+    // 两次产出之间需要的时间
+    pub fn produce_interval(&self) -> u32 {
+        match self {
+            AnimalType::Chicken => 30,
+            AnimalType::Cow => 60,
+            AnimalType::Sheep => 90,
+        }
+    }
+}
+
+// 表示一只动物
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Animal {
+    pub hunger: u32,        // 饥饿度，0 为吃饱
+    pub happiness: u32,     // 心情，0 ~ 100
+    pub produce_timer: u32, // 距离上次产出经过的时间
+}
+
+impl Animal {
+    pub fn new() -> Self {
+        Self {
+            hunger: 0,
+            happiness: 50,
+            produce_timer: 0,
+        }
+    }
+
+    pub fn is_hungry(&self) -> bool {
+        self.hunger >= HUNGRY_THRESHOLD
+    }
+}
+
+// 表示一个放置在地块上的围栏
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pen {
+    pub kind: AnimalType,
+    pub animals: Vec<Animal>,
+}
+
+impl Pen {
+    pub fn new(kind: AnimalType) -> Self {
+        Self { kind, animals: Vec::new() }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.animals.len() >= PEN_CAPACITY
+    }
+
+    // 推进饥饿、心情和产出，返回本次产出的产品数量
+    pub fn tick(&mut self) -> u32 {
+        let interval = self.kind.produce_interval();
+        let mut produced = 0;
+        for animal in self.animals.iter_mut() {
+            animal.hunger = (animal.hunger + 1).min(MAX_HUNGER);
+            // 饥饿的动物心情下降并停止产出
+            if animal.is_hungry() {
+                animal.happiness = animal.happiness.saturating_sub(1);
+                continue;
+            }
+            animal.produce_timer += 1;
+            if animal.produce_timer >= interval {
+                animal.produce_timer = 0;
+                produced += if animal.happiness >= HAPPY_THRESHOLD { 2 } else { 1 };
+            }
+        }
+        produced
+    }
+
+    // 需要喂食的动物数量
+    pub fn hungry_count(&self) -> usize {
+        self.animals.iter().filter(|a| a.hunger > 0).count()
+    }
+
+    // 给最饿的一只动物喂食
+    pub fn feed_one(&mut self) -> bool {
+        match self.animals.iter_mut().filter(|a| a.hunger > 0).max_by_key(|a| a.hunger) {
+            Some(animal) => {
+                animal.hunger = animal.hunger.saturating_sub(FEED_AMOUNT);
+                animal.happiness = (animal.happiness + 5).min(100);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 获取围栏信息
+    pub fn get_info(&self) -> String {
+        let mut info = tf!(
+            "tooltip.pen",
+            icon = self.kind.icon(),
+            name = self.kind.pen_display_name(),
+            count = self.animals.len(),
+            capacity = PEN_CAPACITY
+        );
+        for (i, animal) in self.animals.iter().enumerate() {
+            let status = if animal.is_hungry() { i18n::t("tooltip.pen.hungry") } else { i18n::t("tooltip.pen.fed") };
+            info.push_str(&tf!(
+                "tooltip.pen.animal",
+                name = self.kind.display_name(),
+                number = i + 1,
+                hunger = animal.hunger,
+                happiness = animal.happiness,
+                status = status
+            ));
+        }
+        let feed = self.kind.feed().iter().map(|c| c.display_name().to_string()).collect::<Vec<_>>();
+        info.push_str(&tf!("tooltip.pen.feed", feed = i18n::join(&feed)));
+        info
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+use crate::tile::CropType;
+
+// 每株相邻的同科成熟作物提供的杂交概率
+pub const BREED_CHANCE_PER_NEIGHBOR: f32 = 0.08;
+// 杂交成功时发生变异（直接跳过一个品级）的概率
+pub const MUTATION_CHANCE: f32 = 0.15;
+
+// 表示一次杂交的结果
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BreedingOutcome {
+    Upgrade(CropType),  // 得到高一品级的种子
+    Mutation(CropType), // 变异，得到高两品级的种子
+}
+
+impl BreedingOutcome {
+    pub fn seed(&self) -> CropType {
+        match self {
+            BreedingOutcome::Upgrade(crop) | BreedingOutcome::Mutation(crop) => *crop,
+        }
+    }
+}
+
+// 品种图鉴，记录玩家已经发现的作物品种
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Codex {
+    pub discovered: Vec<CropType>,
+}
+
+impl Codex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 记录新品种，如果是首次发现则返回 true
+    pub fn discover(&mut self, crop: CropType) -> bool {
+        if self.discovered.contains(&crop) {
+            return false;
+        }
+        self.discovered.push(crop);
+        true
+    }
+}
+
+// 根据收获的作物和相邻的同科成熟作物尝试杂交，bonus 为杂交概率倍率
+pub fn try_breed<R: Rng>(crop: CropType, neighbors: &[CropType], bonus: f32, rng: &mut R) -> Option<BreedingOutcome> {
+    let partners = neighbors.iter().filter(|n| n.family() == crop.family()).count();
+    if partners == 0 {
+        return None;
+    }
+
+    // 以父本中较高的品级为基础
+    let base_tier = neighbors
+        .iter()
+        .filter(|n| n.family() == crop.family())
+        .map(|n| n.tier())
+        .chain(std::iter::once(crop.tier()))
+        .max()
+        .unwrap_or(0);
+
+    let chance = BREED_CHANCE_PER_NEIGHBOR * partners as f32 * bonus;
+    if rng.gen::<f32>() >= chance {
+        return None;
+    }
+
+    if rng.gen::<f32>() < MUTATION_CHANCE {
+        if let Some(mutant) = CropType::from_family_tier(crop.family(), base_tier + 2) {
+            return Some(BreedingOutcome::Mutation(mutant));
+        }
+    }
+    CropType::from_family_tier(crop.family(), base_tier + 1).map(BreedingOutcome::Upgrade)
+}
@@ -0,0 +1,61 @@
+// 用固定种子的随机数检查杂交结果：概率随相邻作物增加，品级按父本中最高的计算
+use crate::breeding::{self, BreedingOutcome, BREED_CHANCE_PER_NEIGHBOR, MUTATION_CHANCE};
+use crate::tile::CropType;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// 足够大的倍率让每次都能杂交成功，只比较结果
+const ALWAYS: f32 = 100.0;
+
+fn outcomes(crop: CropType, neighbors: &[CropType], bonus: f32, trials: u32) -> Vec<Option<BreedingOutcome>> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..trials).map(|_| breeding::try_breed(crop, neighbors, bonus, &mut rng)).collect()
+}
+
+#[test]
+fn breeding_needs_a_partner_of_the_same_family() {
+    assert!(outcomes(CropType::Wheat, &[], ALWAYS, 100).iter().all(Option::is_none));
+    assert!(outcomes(CropType::Wheat, &[CropType::Corn, CropType::Apple], ALWAYS, 100).iter().all(Option::is_none));
+    assert!(outcomes(CropType::Strawberry, &[CropType::Strawberry], ALWAYS, 100).iter().all(Option::is_none));
+}
+
+#[test]
+fn basic_crops_upgrade_or_mutate() {
+    let results = outcomes(CropType::Wheat, &[CropType::Wheat], ALWAYS, 2000);
+    let mutations = results.iter().filter(|r| **r == Some(BreedingOutcome::Mutation(CropType::GoldenWheat))).count();
+    let upgrades = results.iter().filter(|r| **r == Some(BreedingOutcome::Upgrade(CropType::PremiumWheat))).count();
+    assert_eq!(mutations + upgrades, results.len());
+    let rate = mutations as f32 / results.len() as f32;
+    assert!((rate - MUTATION_CHANCE).abs() < 0.03, "变异比例 {}", rate);
+}
+
+#[test]
+fn premium_crops_breed_golden_seeds() {
+    // 优质作物没有更高两级的品种，变异时也得到金色种子
+    let results = outcomes(CropType::PremiumCorn, &[CropType::PremiumCorn], ALWAYS, 500);
+    assert!(results.iter().all(|r| *r == Some(BreedingOutcome::Upgrade(CropType::GoldenCorn))));
+    // 以父本中较高的品级为基础
+    let results = outcomes(CropType::Carrot, &[CropType::PremiumCarrot], ALWAYS, 500);
+    assert!(results.iter().all(|r| *r == Some(BreedingOutcome::Upgrade(CropType::GoldenCarrot))));
+    // 金色作物已是最高品级
+    assert!(outcomes(CropType::GoldenWheat, &[CropType::GoldenWheat], ALWAYS, 100).iter().all(Option::is_none));
+}
+
+#[test]
+fn more_neighbors_raise_the_chance() {
+    let trials = 5000;
+    let rate = |neighbors: &[CropType]| {
+        let bred = outcomes(CropType::Corn, neighbors, 1.0, trials).iter().filter(|r| r.is_some()).count();
+        bred as f32 / trials as f32
+    };
+    let one = rate(&[CropType::Corn]);
+    let three = rate(&[CropType::Corn, CropType::Corn, CropType::PremiumCorn]);
+    assert!((one - BREED_CHANCE_PER_NEIGHBOR).abs() < 0.02, "一株相邻作物的杂交比例 {}", one);
+    assert!((three - BREED_CHANCE_PER_NEIGHBOR * 3.0).abs() < 0.03, "三株相邻作物的杂交比例 {}", three);
+}
+
+#[test]
+fn the_same_seed_gives_the_same_outcomes() {
+    let neighbors = [CropType::Wheat, CropType::PremiumWheat];
+    assert!(outcomes(CropType::Wheat, &neighbors, 1.0, 500) == outcomes(CropType::Wheat, &neighbors, 1.0, 500));
+}
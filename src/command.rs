@@ -0,0 +1,138 @@
+use serde::{Serialize, Deserialize};
+use crate::farm::AreaSummary;
+use crate::rules::Rule;
+
+// 表示一次玩家操作，所有会修改游戏状态的入口都通过它执行
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum Command {
+    Plant { row: usize, col: usize, crop: String },
+    Harvest { row: usize, col: usize },
+    ClearTile { row: usize, col: usize },
+    // 旧日志中的施肥操作没有记录肥料，回放时使用当时选择的肥料
+    Fertilize {
+        row: usize,
+        col: usize,
+        #[serde(default)]
+        fertilizer: Option<String>,
+    },
+    SelectFertilizer { fertilizer: String },
+    SprayTile { row: usize, col: usize },
+    ApplyBugProtection,
+    BuySeed { seed: String },
+    BuyFertilizer { fertilizer: String },
+    SellCrop { crop: String },
+    ExtractSeeds { crop: String },
+    BuyMachine { machine: String },
+    PlaceMachine { row: usize, col: usize, machine: String },
+    StartProcessing { row: usize, col: usize, input: Option<String> },
+    SellGood { good: String },
+    BuyStructure { structure: String },
+    PlaceStructure { row: usize, col: usize, structure: String },
+    BuyPen { pen: String },
+    PlacePen { row: usize, col: usize, pen: String },
+    BuyAnimal { row: usize, col: usize },
+    FeedAnimals { row: usize, col: usize },
+    ClaimTaskReward { task_id: u32 },
+    ClaimQuestStep { chain_id: String },
+    Undo,
+    HarvestAll,
+    PlantRect { r0: usize, c0: usize, r1: usize, c1: usize, crop: String },
+    FertilizeRect {
+        r0: usize,
+        c0: usize,
+        r1: usize,
+        c1: usize,
+        #[serde(default)]
+        fertilizer: Option<String>,
+    },
+    SprayAllInfested,
+    HireWorker { role: String, crop: Option<String>, r0: usize, c0: usize, r1: usize, c1: usize },
+    FireWorker { worker_id: u32 },
+    AddRule { rule: Rule },
+    RemoveRule { rule_id: u32 },
+    SetRuleEnabled { rule_id: u32, enabled: bool },
+}
+
+// 表示操作成功后的结果，失败时由 GameError 表示原因
+pub enum Outcome {
+    Done,              // 没有返回值的操作
+    Count(u32),        // 操作影响的数量
+    Area(AreaSummary), // 批量操作的结果汇总
+}
+
+impl Outcome {
+    pub fn count(&self) -> u32 {
+        match *self {
+            Outcome::Done => 1,
+            Outcome::Count(count) => count,
+            Outcome::Area(ref summary) => summary.succeeded,
+        }
+    }
+}
+
+// 操作日志中的一条记录
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoggedCommand {
+    pub tick: u64, // 执行操作时农场经历的时间流逝次数
+    pub command: Command,
+}
+
+// 日志中最多保留的操作数量，达到后以当前存档作为检查点，只保留之后的操作
+pub const MAX_LOGGED_COMMANDS: usize = 1000;
+
+// 操作日志，配合随机种子可以从新游戏或检查点重建完整的游戏状态
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ActionLog {
+    pub seed: u64,
+    pub replayable: bool,           // 旧存档没有从新游戏开始记录，无法回放
+    #[serde(default)]
+    pub checkpoint: Option<String>, // 检查点的存档，回放从这里开始而不是从新游戏开始
+    #[serde(default)]
+    pub trimmed: u64,               // 已并入检查点的操作数量
+    pub entries: Vec<LoggedCommand>,
+    #[serde(default)]
+    pub end_tick: u64,              // 导出日志时的时间流逝次数，回放时推进到该时刻
+}
+
+impl ActionLog {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            replayable: true,
+            checkpoint: None,
+            trimmed: 0,
+            entries: Vec::new(),
+            end_tick: 0,
+        }
+    }
+
+    pub fn push(&mut self, tick: u64, command: Command) {
+        self.entries.push(LoggedCommand { tick, command });
+    }
+
+    // 下一条操作的序号，检查点之前的操作也计算在内，使随机数步骤不受截断影响
+    pub fn next_step(&self) -> u64 {
+        self.trimmed + self.entries.len() as u64 + 1
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.entries.len() >= MAX_LOGGED_COMMANDS
+    }
+
+    // 以存档作为新的检查点，丢弃之前的操作
+    pub fn set_checkpoint(&mut self, state: String) {
+        self.trimmed += self.entries.len() as u64;
+        self.entries.clear();
+        self.checkpoint = Some(state);
+    }
+
+    // 从检查点回放时使用的日志，回放的操作会重新记入其中
+    pub fn restart(&self) -> Self {
+        Self {
+            checkpoint: self.checkpoint.clone(),
+            entries: Vec::new(),
+            end_tick: 0,
+            ..*self
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+use crate::i18n::{self, t, tf};
+
+// 玩家操作失败的原因，传给界面时序列化为 { code, params }
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "code", content = "params", rename_all = "snake_case")]
+pub enum GameError {
+    OutOfBounds { row: usize, col: usize },
+    TileLocked,
+    TileOccupied,
+    NothingToHarvest,
+    NoPests,
+    NotFertilizable, // 地块上没有正在生长的作物
+    AlreadyFertilized,
+    InsufficientFunds { price: u32, balance: u32 },
+    UnknownItem { id: String, kind: &'static str },
+    NotForSale { item: String },
+    ItemLocked { item: String, level: Option<u32> },
+    NoItemInInventory { item: String },
+    BackpackFull { needed: u32 },
+    StorageFull,
+    StorageInUse, // 拆除储存建筑后仓库放不下现有物品
+    NoMachine,
+    MachineBusy,
+    NoRecipe,
+    SeedMakerFull,
+    NoPen,
+    PenFull,
+    PenNotEmpty,
+    NothingToFeed,
+    NothingToClear,
+    UnknownWorkerRole { role: String },
+    WorkerLimit { max: usize },
+    WorkerNotFound { worker_id: u32 },
+    RuleLimit { max: usize },
+    RuleNotFound { rule_id: u32 },
+    TaskNotClaimable { task_id: u32 },
+    QuestStepNotReady { chain_id: String },
+    NothingToUndo,
+    UndoExpired,
+    UndoLevelChanged,
+    UndoTileChanged,
+    UndoItemsUsed,
+}
+
+impl GameError {
+    // 显示给玩家的提示文字
+    pub fn message(&self) -> String {
+        match self {
+            GameError::OutOfBounds { row, col } => tf!("error.out_of_bounds", row = row, col = col),
+            GameError::TileLocked => t("error.tile_locked").to_string(),
+            GameError::TileOccupied => t("error.tile_occupied").to_string(),
+            GameError::NothingToHarvest => t("error.nothing_to_harvest").to_string(),
+            GameError::NoPests => t("error.no_pests").to_string(),
+            GameError::NotFertilizable => t("error.not_fertilizable").to_string(),
+            GameError::AlreadyFertilized => t("error.already_fertilized").to_string(),
+            GameError::InsufficientFunds { price, balance } => {
+                tf!("error.insufficient_funds", price = i18n::number(*price), balance = i18n::number(*balance))
+            }
+            GameError::UnknownItem { id, kind } => tf!("error.unknown_item", kind = kind_name(kind), id = printable_id(id)),
+            GameError::NotForSale { item } => tf!("error.not_for_sale", item = item_name(item)),
+            GameError::ItemLocked { level: Some(level), .. } => tf!("error.item_locked_level", level = level),
+            GameError::ItemLocked { level: None, .. } => t("error.item_locked_quest").to_string(),
+            GameError::NoItemInInventory { item } => tf!("error.no_item_in_inventory", item = item_name(item)),
+            GameError::BackpackFull { needed } if *needed > 1 => tf!("error.backpack_needs_room", count = needed),
+            GameError::BackpackFull { .. } => t("error.backpack_full").to_string(),
+            GameError::StorageFull => t("error.storage_full").to_string(),
+            GameError::StorageInUse => t("error.storage_in_use").to_string(),
+            GameError::NoMachine => t("error.no_machine").to_string(),
+            GameError::MachineBusy => t("error.machine_busy").to_string(),
+            GameError::NoRecipe => t("error.no_recipe").to_string(),
+            GameError::SeedMakerFull => t("error.seed_maker_full").to_string(),
+            GameError::NoPen => t("error.no_pen").to_string(),
+            GameError::PenFull => t("error.pen_full").to_string(),
+            GameError::PenNotEmpty => t("error.pen_not_empty").to_string(),
+            GameError::NothingToFeed => t("error.nothing_to_feed").to_string(),
+            GameError::NothingToClear => t("error.nothing_to_clear").to_string(),
+            GameError::UnknownWorkerRole { .. } => t("error.unknown_worker_role").to_string(),
+            GameError::WorkerLimit { max } => tf!("error.worker_limit", count = max),
+            GameError::WorkerNotFound { .. } => t("error.worker_not_found").to_string(),
+            GameError::RuleLimit { max } => tf!("error.rule_limit", count = max),
+            GameError::RuleNotFound { .. } => t("error.rule_not_found").to_string(),
+            GameError::TaskNotClaimable { .. } => t("error.task_not_claimable").to_string(),
+            GameError::QuestStepNotReady { .. } => t("error.quest_step_not_ready").to_string(),
+            GameError::NothingToUndo => t("error.nothing_to_undo").to_string(),
+            GameError::UndoExpired => t("error.undo_expired").to_string(),
+            GameError::UndoLevelChanged => t("error.undo_level_changed").to_string(),
+            GameError::UndoTileChanged => t("error.undo_tile_changed").to_string(),
+            GameError::UndoItemsUsed => t("error.undo_items_used").to_string(),
+        }
+    }
+}
+
+// 物品种类的显示名称
+fn kind_name(kind: &str) -> &'static str {
+    match kind {
+        "crop" | "fertilizer" | "machine" | "structure" | "pen" | "good" => t(&format!("item_kind.{}", kind)),
+        _ => t("item_kind.item"),
+    }
+}
+
+// 物品的显示名称，未知物品直接显示编号
+fn item_name(id: &str) -> String {
+    crate::item::ItemId::parse(id).map(|item| item.display_name().to_string()).unwrap_or_else(|_| printable_id(id))
+}
+
+// 未知的编号可能来自拖入页面的任意文本，只保留编号中会出现的字符并限制长度
+fn printable_id(id: &str) -> String {
+    id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-').take(32).collect()
+}
@@ -1,178 +1,860 @@
-use super::tile::{CropType, Tile, TileState, FertilizerType};
-use super::inventory::Inventory;
+use super::tile::{CropType, Tile, TileContent, TileInfo, TileModifier, TileSnapshot, TileState, TileStatus, FertilizerType};
+use super::inventory::{self, Inventory, Pool};
+use super::breeding::{self, BreedingOutcome, Codex};
+use super::seed_maker::SeedMaker;
+use super::processing::Machine;
+use super::structure::{self, StructureType};
+use super::animal::{AnimalType, Animal, Pen};
+use super::progression;
+use super::worker::{Crew, WorkResult, WorkerRole};
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use crate::error::GameError;
+use crate::item::ItemId;
+use crate::i18n::{t, tf};
+use crate::utils::show_message;
 use rand::Rng;
 
+// 每个游戏日包含的时间流逝次数
+pub const TICKS_PER_DAY: u64 = 120;
+// 新农场底部尚未开垦的行数
+pub const LOCKED_ROWS: usize = 2;
+// 生长中的作物每次时间流逝变成虫害的概率
+pub const PEST_CHANCE: f32 = 0.02;
+
+// 表示一次收获的结果
+pub struct HarvestResult {
+    pub crop: CropType,
+    pub breeding: Option<BreedingOutcome>, // 与相邻作物杂交的结果
+}
+
+// 一帧渲染和界面刷新所需的全部状态，一次调用取得
+#[derive(Serialize)]
+pub struct FarmSnapshot {
+    pub rows: usize,
+    pub cols: usize,
+    pub tiles: Vec<TileSnapshot>, // 按行排列，下标为 row * cols + col
+    pub balance: u32,
+    pub seeds: HashMap<ItemId, u32>,
+    pub crops: HashMap<ItemId, u32>,
+    pub fertilizers: HashMap<ItemId, u32>,
+    pub goods: HashMap<ItemId, u32>,
+    pub buildings: HashMap<ItemId, u32>,
+    pub items: HashMap<ItemId, u32>,
+}
+
+// 批量操作中地块被跳过的原因
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Locked,          // 土地尚未开垦
+    Building,        // 地块上有建筑
+    Occupied,        // 地块上已有作物
+    NoCrop,          // 地块上没有作物
+    NotFertilizable, // 作物已施肥或已成熟
+    OutOfSeeds,
+    OutOfFertilizer,
+    StorageFull,     // 仓库已满，无法收获
+    NotReady,        // 作物尚未成熟
+    NoPests,         // 地块上没有虫害
+    Failed,          // 其他原因，详见单块地操作的错误
+}
+
+impl SkipReason {
+    pub fn message(&self) -> &'static str {
+        t(match self {
+            SkipReason::Locked => "skip.locked",
+            SkipReason::Building => "skip.building",
+            SkipReason::Occupied => "skip.occupied",
+            SkipReason::NoCrop => "skip.no_crop",
+            SkipReason::NotFertilizable => "skip.not_fertilizable",
+            SkipReason::OutOfSeeds => "skip.out_of_seeds",
+            SkipReason::OutOfFertilizer => "skip.out_of_fertilizer",
+            SkipReason::StorageFull => "skip.storage_full",
+            SkipReason::NotReady => "skip.not_ready",
+            SkipReason::NoPests => "skip.no_pests",
+            SkipReason::Failed => "skip.failed",
+        })
+    }
+
+    // 单块地操作失败的原因对应的跳过原因，缺少的物品按编号区分种子和肥料
+    pub fn from_error(err: &GameError) -> Self {
+        match err {
+            GameError::TileLocked => SkipReason::Locked,
+            GameError::TileOccupied => SkipReason::Occupied,
+            GameError::NothingToHarvest => SkipReason::NotReady,
+            GameError::NoPests => SkipReason::NoPests,
+            GameError::NotFertilizable | GameError::AlreadyFertilized => SkipReason::NotFertilizable,
+            GameError::StorageFull => SkipReason::StorageFull,
+            GameError::NoItemInInventory { item } => match ItemId::parse(item) {
+                Ok(ItemId::Crop(_)) => SkipReason::OutOfSeeds,
+                Ok(ItemId::Fertilizer(_)) => SkipReason::OutOfFertilizer,
+                _ => SkipReason::Failed,
+            },
+            _ => SkipReason::Failed,
+        }
+    }
+}
+
+// 一种跳过原因的地块数量
+#[derive(Serialize)]
+pub struct SkipCount {
+    pub reason: SkipReason,
+    pub message: &'static str,
+    pub count: u32,
+}
+
+// 批量操作的结果汇总
+#[derive(Serialize, Default)]
+pub struct AreaSummary {
+    pub succeeded: u32,
+    pub skipped: Vec<SkipCount>,
+}
+
+impl AreaSummary {
+    fn skip(&mut self, reason: SkipReason) {
+        match self.skipped.iter_mut().find(|s| s.reason == reason) {
+            Some(entry) => entry.count += 1,
+            None => self.skipped.push(SkipCount { reason, message: reason.message(), count: 1 }),
+        }
+    }
+}
+
 // 表示一个农场，包含瓦片网格和库存
 #[derive(Serialize, Deserialize)]
 pub struct Farm {
     pub grid: Vec<Vec<Tile>>,  // 农场网格，每个瓦片包含状态和作物信息
     pub inventory: Inventory,  // 库存，包含种子、肥料和作物
+    #[serde(default)]
+    pub codex: Codex,          // 品种图鉴，记录已发现的作物品种
+    #[serde(default)]
+    pub seed_maker: SeedMaker, // 制种机，把作物加工回种子
+    #[serde(default)]
+    pub ticks: u64,            // 农场经历的时间流逝次数
+    #[serde(default)]
+    pub xp: u32,               // 玩家获得的经验
+    #[serde(default = "progression::legacy_level")]
+    pub level: u32,            // 玩家等级
+    #[serde(default)]
+    pub crew: Crew,            // 雇佣的帮工
 }
 
 impl Farm {
     // 创建一个新的农场，初始化网格和库存
     pub fn new(width: usize, height: usize) -> Self {
         // 创建一个宽为 width，高为 height 的网格，每个瓦片初始化为空状态
-        let grid = vec![vec![Tile::new(); width]; height];
+        let mut grid = vec![vec![Tile::new(); width]; height];
+        // 底部几行需要通过奖励开垦
+        for row in grid.iter_mut().skip(height.saturating_sub(LOCKED_ROWS)) {
+            for tile in row.iter_mut() {
+                tile.content = TileContent::Locked;
+            }
+        }
         // 创建一个新的库存，用于管理种子、肥料和作物
         Self {
             grid,
             inventory: Inventory::new(),
+            codex: Codex::new(),
+            seed_maker: SeedMaker::new(),
+            ticks: 0,
+            xp: 0,
+            level: 1,
+            crew: Crew::new(),
+        }
+    }
+
+    // 增加经验，升级时开垦新的土地，返回提升的等级数
+    pub fn add_xp(&mut self, amount: u32) -> u32 {
+        self.xp += amount;
+        let new_level = progression::level_for_xp(self.xp).max(self.level);
+        let gained = new_level - self.level;
+        self.level = new_level;
+        self.unlock_plots(gained * progression::LAND_PER_LEVEL);
+        gained
+    }
+
+    // 按从上到下、从左到右的顺序开垦土地，返回实际开垦的数量
+    pub fn unlock_plots(&mut self, plots: u32) -> u32 {
+        let mut unlocked = 0;
+        for tile in self.grid.iter_mut().flatten() {
+            if unlocked == plots {
+                break;
+            }
+            if matches!(tile.content, TileContent::Locked) {
+                tile.content = TileContent::Soil;
+                unlocked += 1;
+            }
         }
+        unlocked
     }
+
+    // 当前的游戏天数，从 0 开始
+    pub fn day(&self) -> u32 {
+        (self.ticks / TICKS_PER_DAY) as u32
+    }
+
+    // 获取上下左右四个相邻地块的坐标
+    pub fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        if row > 0 {
+            result.push((row - 1, col));
+        }
+        if row + 1 < self.grid.len() {
+            result.push((row + 1, col));
+        }
+        if col > 0 {
+            result.push((row, col - 1));
+        }
+        if col + 1 < self.grid[0].len() {
+            result.push((row, col + 1));
+        }
+        result
+    }
+
+    // 获取相邻地块中已成熟的作物
+    fn mature_neighbors(&self, row: usize, col: usize) -> Vec<CropType> {
+        self.neighbors(row, col)
+            .into_iter()
+            .filter_map(|(r, c)| match self.grid[r][c].state {
+                TileState::Mature { crop, .. } => Some(crop),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 计算指定建筑的影响范围，返回与网格同尺寸的覆盖表
+    pub fn structure_coverage(&self, kind: StructureType) -> Vec<Vec<bool>> {
+        let height = self.grid.len();
+        let width = self.grid[0].len();
+        let radius = kind.effect_radius();
+        let mut covered = vec![vec![false; width]; height];
+        for (r, row) in self.grid.iter().enumerate() {
+            for (c, tile) in row.iter().enumerate() {
+                if tile.structure() != Some(kind) {
+                    continue;
+                }
+                let rows = r.saturating_sub(radius)..(r + radius + 1).min(height);
+                let cols = c.saturating_sub(radius)..(c + radius + 1).min(width);
+                for covered_row in &mut covered[rows] {
+                    for cell in &mut covered_row[cols.clone()] {
+                        *cell = true;
+                    }
+                }
+            }
+        }
+        covered
+    }
+
     // 处理农场中的时间流逝，不考虑虫害
     pub fn tick_without_infestation(&mut self) {
+        self.advance();
+    }
+
+    // 处理农场中的时间流逝，考虑虫害，返回本次遭遇虫害的作物
+    pub fn tick(&mut self) -> Vec<CropType> {
+        self.advance();
+        self.random_infest()
+    }
+
+    // 两种时间流逝共用的部分：作物生长、仓库中的作物变得不新鲜，以及推进制种机、机器和围栏
+    fn advance(&mut self) {
+        self.ticks += 1;
+        self.grow_crops();
+        self.inventory.age_crops(self.spoilage_rate());
+        self.tick_seed_maker();
+        self.tick_machines();
+        self.tick_pens();
+    }
+
+    // 推进所有生长中的作物，到达生长时间后成熟
+    fn grow_crops(&mut self) {
+        // 洒水器范围内的作物定期额外生长一次
+        let watered = self.structure_coverage(StructureType::Sprinkler);
+        let sprinkler_bonus = self.ticks.is_multiple_of(structure::SPRINKLER_BONUS_INTERVAL);
         // 遍历网格中的每一行
-        for row in self.grid.iter_mut() {
+        for (r, row) in self.grid.iter_mut().enumerate() {
             // 遍历当前行中的每个瓦片
-            for tile in row.iter_mut() {
+            for (c, tile) in row.iter_mut().enumerate() {
                 // 如果当前瓦片处于种植状态，则增加计时器
-                match &mut tile.state {
-                    TileState::Planted { timer, .. } => {
+                if let TileState::Planted { crop, timer, fertilizer, harvests } = &mut tile.state {
+                    *timer += 1;
+                    if sprinkler_bonus && watered[r][c] {
                         *timer += 1;
-                        // 如果计时器达到生长时间，则将作物状态改为成熟
-                        if *timer >= 5 {
-                            if let TileState::Planted { crop, .. } = std::mem::replace(&mut tile.state, TileState::Empty) {
-                                // 将作物状态改为成熟
-                                tile.state = TileState::Mature { crop };
-                            }
-                        }
                     }
-                    _ => {}
+                    // 使用 CropType 中定义的统一方法，多季作物再生阶段使用再生时间
+                    let adjusted_time = crop.growth_time(*fertilizer, *harvests);
+                    if *timer >= adjusted_time {
+                        tile.state = TileState::Mature { crop: *crop, harvests: *harvests };
+                    }
                 }
             }
         }
     }
 
-    // 处理农场中的时间流逝，考虑虫害
-    pub fn tick(&mut self) {
-        // 遍历网格中的每一行
+    // 推进制种机，完成的种子放入库存
+    fn tick_seed_maker(&mut self) {
+        let finished = crate::rng::with_rng(|rng| self.seed_maker.tick(rng));
+        let mut dropped = 0;
+        for (crop, count) in finished {
+            for _ in 0..count {
+                if !self.inventory.add_seed(crop) {
+                    dropped += 1;
+                }
+            }
+        }
+        if dropped > 0 {
+            show_message(&tf!("message.seeds_dropped", count = dropped));
+        }
+    }
+
+    // 推进所有加工机器，完成的加工品放入库存
+    fn tick_machines(&mut self) {
+        let mut dropped = 0;
         for row in self.grid.iter_mut() {
-            // 遍历当前行中的每个瓦片
             for tile in row.iter_mut() {
-                // 如果当前瓦片处于种植状态，则增加计时器
-                if let TileState::Planted { crop, timer, fertilizer } = &mut tile.state {
-                    *timer += 1;
-                    // 使用 CropType 中定义的统一方法
-                    let adjusted_time = crop.growth_time_with_fertilizer(*fertilizer);
-                    if *timer >= adjusted_time {
-                        tile.state = TileState::Mature { crop: *crop };
+                if let Some((good, count)) = tile.machine_mut().and_then(|m| m.tick()) {
+                    dropped += self.inventory.add_good(good, count);
+                }
+            }
+        }
+        if dropped > 0 {
+            show_message(&tf!("message.goods_dropped", count = dropped));
+        }
+    }
+
+    // 推进所有围栏中的动物，产出的产品放入库存
+    fn tick_pens(&mut self) {
+        let mut dropped = 0;
+        for row in self.grid.iter_mut() {
+            for tile in row.iter_mut() {
+                if let Some(pen) = tile.pen_mut() {
+                    let produced = pen.tick();
+                    if produced > 0 {
+                        let product = pen.kind.product();
+                        dropped += self.inventory.add_good(ItemId::Good(product), produced);
                     }
                 }
             }
         }
-        self.random_infest(); 
+        if dropped > 0 {
+            show_message(&tf!("message.products_dropped", count = dropped));
+        }
     }
 
-    // 种植作物，如果成功种植则返回 true
-    pub fn plant(&mut self, row: usize, col: usize, crop: CropType, seed_key: String) -> bool {
-        // 检查坐标是否在网格范围内
-        if row < self.grid.len() && col < self.grid[0].len() {
-            // 获取指定位置的瓦片
-            let tile = &mut self.grid[row][col];
-            // 检查瓦片是否可以种植
-            if tile.can_plant() && self.inventory.remove_seed(&seed_key) {
-                tile.state = TileState::Planted {
-                    crop,
-                    timer: 0,
-                    fertilizer: FertilizerType::None,
-                };
-                return true;
-            }
-        }
-        false
-    }
-
-    // 收获作物，如果成功收获则返回 true
-    pub fn harvest(&mut self, row: usize, col: usize) {
-        // 检查坐标是否在网格范围内
-        if row < self.grid.len() && col < self.grid[0].len() {
-            // 获取指定位置的瓦片
-            let tile = &mut self.grid[row][col];
-            // 如果当前瓦片处于成熟状态，则收获作物
-            if let TileState::Mature { crop } = tile.state {
-                let crop_str = match crop {
-                    CropType::Wheat => "wheat",
-                    CropType::PremiumWheat => "premium_wheat",
-                    CropType::GoldenWheat => "golden_wheat",
-                    CropType::Corn => "corn",
-                    CropType::PremiumCorn => "premium_corn",
-                    CropType::GoldenCorn => "golden_corn",
-                    CropType::Carrot => "carrot",
-                    CropType::PremiumCarrot => "premium_carrot",
-                    CropType::GoldenCarrot => "golden_carrot",
-                };
-                self.inventory.add_crop(crop_str);
-                tile.state = TileState::Empty;
+    // 从库存中取出围栏放置到空地上
+    pub fn place_pen(&mut self, row: usize, col: usize, pen_id: &str) -> Result<(), GameError> {
+        let kind = crate::item::parse_pen(pen_id)?;
+        self.check_buildable(row, col)?;
+        self.take_building(ItemId::Pen(kind))?;
+        self.grid[row][col].content = TileContent::Pen(Pen::new(kind));
+        Ok(())
+    }
+
+    // 拆除空围栏并放回库存，围栏中还有动物时不能拆除
+    pub fn remove_pen(&mut self, row: usize, col: usize) -> Result<(), GameError> {
+        let pen = self.tile(row, col)?.pen().ok_or(GameError::NoPen)?;
+        if !pen.animals.is_empty() {
+            return Err(GameError::PenNotEmpty);
+        }
+        let pen = ItemId::Pen(pen.kind);
+        self.inventory.add_building(pen);
+        self.grid[row][col].content = TileContent::Soil;
+        Ok(())
+    }
+
+    // 获取可以容纳新动物的围栏，返回其中的动物类型
+    pub fn pen_with_room(&self, row: usize, col: usize) -> Result<AnimalType, GameError> {
+        match self.tile(row, col)?.pen() {
+            Some(pen) if pen.is_full() => Err(GameError::PenFull),
+            Some(pen) => Ok(pen.kind),
+            None => Err(GameError::NoPen),
+        }
+    }
+
+    // 向围栏中添加一只动物，需要调用方先完成购买
+    pub fn add_animal(&mut self, row: usize, col: usize) -> bool {
+        if self.pen_with_room(row, col).is_err() {
+            return false;
+        }
+        match self.grid[row][col].pen_mut() {
+            Some(pen) => {
+                pen.animals.push(Animal::new());
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 用库存中的作物喂食围栏中的动物，返回喂食的次数
+    pub fn feed_pen(&mut self, row: usize, col: usize) -> Result<u32, GameError> {
+        self.check_bounds(row, col)?;
+        let pen = self.grid[row][col].pen_mut().ok_or(GameError::NoPen)?;
+        let mut fed = 0;
+        for _ in 0..pen.hungry_count() {
+            // 按饲料优先顺序从库存中取出一株作物
+            let has_food = pen.kind.feed().iter().any(|c| self.inventory.remove_crop(*c));
+            if !has_food || !pen.feed_one() {
+                break;
+            }
+            fed += 1;
+        }
+        if fed == 0 {
+            return Err(GameError::NothingToFeed);
+        }
+        Ok(fed)
+    }
+
+    // 从库存中取出机器放置到空地上
+    pub fn place_machine(&mut self, row: usize, col: usize, machine_id: &str) -> Result<(), GameError> {
+        let kind = crate::item::parse_machine(machine_id)?;
+        self.check_buildable(row, col)?;
+        self.take_building(ItemId::Machine(kind))?;
+        self.grid[row][col].content = TileContent::Machine(Machine::new(kind));
+        Ok(())
+    }
+
+    // 拆除地块上的机器并放回库存，正在进行的加工会被取消，没有机器时返回 false
+    pub fn remove_machine(&mut self, row: usize, col: usize) -> bool {
+        let Some(kind) = self.tile(row, col).ok().and_then(|tile| tile.machine()).map(|m| m.kind) else {
+            return false;
+        };
+        self.inventory.add_building(ItemId::Machine(kind));
+        self.grid[row][col].content = TileContent::Soil;
+        true
+    }
+
+    // 从库存中取出建筑放置到地块上，多格建筑以 (row, col) 为左上角
+    pub fn place_structure(&mut self, row: usize, col: usize, structure_id: &str) -> Result<(), GameError> {
+        let kind = crate::item::parse_structure(structure_id)?;
+        let (height, width) = kind.footprint();
+        // 先检查左上角，之后计算其余地块的坐标不会溢出
+        self.tile(row, col)?;
+        // 建筑占用的所有地块都必须在网格内且是空地
+        for r in row..row + height {
+            for c in col..col + width {
+                self.check_buildable(r, c)?;
+            }
+        }
+        self.take_building(ItemId::Structure(kind))?;
+        for r in row..row + height {
+            for c in col..col + width {
+                self.grid[r][c].content = TileContent::Structure { kind, origin: (row, col) };
+            }
+        }
+        self.refresh_capacity();
+        Ok(())
+    }
+
+    // 拆除地块所在的整个建筑并放回库存，没有建筑时返回 Ok(false)
+    pub fn remove_structure(&mut self, row: usize, col: usize) -> Result<bool, GameError> {
+        let TileContent::Structure { kind, origin } = self.tile(row, col)?.content else {
+            return Ok(false);
+        };
+        // 拆除储存建筑后仓库必须还能放下现有的物品
+        let remaining = self.inventory.capacity(Pool::Storage).saturating_sub(kind.storage_bonus());
+        if self.inventory.used(Pool::Storage) > remaining {
+            return Err(GameError::StorageInUse);
+        }
+        let (height, width) = kind.footprint();
+        for r in origin.0..origin.0 + height {
+            for c in origin.1..origin.1 + width {
+                self.grid[r][c].content = TileContent::Soil;
+            }
+        }
+        self.inventory.add_building(ItemId::Structure(kind));
+        self.refresh_capacity();
+        Ok(true)
+    }
+
+    // 仓库中作物的腐败速度，储存建筑越多腐败越慢
+    pub fn spoilage_rate(&self) -> f32 {
+        let bonus = self.inventory.storage_capacity.saturating_sub(inventory::BASE_STORAGE_CAPACITY);
+        1.0 / (1.0 + bonus as f32 / 200.0)
+    }
+
+    // 根据已放置的储存建筑重新计算仓库容量
+    pub fn refresh_capacity(&mut self) {
+        let bonus: u32 = self
+            .grid
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.iter().enumerate().map(move |(c, tile)| (r, c, tile)))
+            .filter_map(|(r, c, tile)| match tile.content {
+                TileContent::Structure { kind, origin } if origin == (r, c) => Some(kind.storage_bonus()),
+                _ => None,
+            })
+            .sum();
+        self.inventory.storage_capacity = inventory::BASE_STORAGE_CAPACITY + bonus;
+    }
+
+    // 在机器上开始加工，未指定原料时使用第一个库存足够的配方
+    pub fn start_processing(&mut self, row: usize, col: usize, input: Option<CropType>) -> Result<(), GameError> {
+        self.check_bounds(row, col)?;
+        let machine = self.grid[row][col].machine_mut().ok_or(GameError::NoMachine)?;
+        if machine.is_busy() {
+            return Err(GameError::MachineBusy);
+        }
+        let inventory = &self.inventory;
+        let recipe = match input {
+            Some(input) => machine.kind.find_recipe(input),
+            None => machine.kind.recipes().find(|r| {
+                inventory.crop_count(r.input) >= r.input_count
+            }),
+        };
+        match recipe {
+            Some(recipe) if self.inventory.remove_crops(recipe.input, recipe.input_count) => {
+                machine.start(recipe);
+                Ok(())
+            }
+            _ => Err(GameError::NoRecipe),
+        }
+    }
+
+    // 把库存中的一株作物放入制种机
+    pub fn extract_seeds(&mut self, crop_id: &str) -> Result<(), GameError> {
+        let crop = crate::item::parse_crop(crop_id)?;
+        if self.seed_maker.is_full() {
+            return Err(GameError::SeedMakerFull);
+        }
+        if !self.inventory.remove_crop(crop) {
+            return Err(GameError::NoItemInInventory { item: crop_id.to_string() });
+        }
+        self.seed_maker.start(crop);
+        Ok(())
+    }
+
+    // 按坐标获取地块，所有来自界面的坐标都通过这里检查是否在网格范围内
+    pub fn tile(&self, row: usize, col: usize) -> Result<&Tile, GameError> {
+        self.grid.get(row).and_then(|r| r.get(col)).ok_or(GameError::OutOfBounds { row, col })
+    }
+
+    pub fn tile_mut(&mut self, row: usize, col: usize) -> Result<&mut Tile, GameError> {
+        self.grid.get_mut(row).and_then(|r| r.get_mut(col)).ok_or(GameError::OutOfBounds { row, col })
+    }
+
+    // 检查坐标是否在网格范围内，之后可以直接按下标访问网格
+    pub fn check_bounds(&self, row: usize, col: usize) -> Result<(), GameError> {
+        self.tile(row, col).map(|_| ())
+    }
+
+    // 检查地块是否为可以种植或放置建筑的空地
+    fn check_buildable(&self, row: usize, col: usize) -> Result<(), GameError> {
+        let tile = self.tile(row, col)?;
+        match tile.content {
+            TileContent::Locked => Err(GameError::TileLocked),
+            _ if !tile.can_plant() => Err(GameError::TileOccupied),
+            _ => Ok(()),
+        }
+    }
+
+    // 从库存中取出一个待放置的建筑
+    fn take_building(&mut self, building: ItemId) -> Result<(), GameError> {
+        if self.inventory.remove_building(building) {
+            Ok(())
+        } else {
+            Err(GameError::NoItemInInventory { item: building.id().to_string() })
+        }
+    }
+
+    // 种植作物
+    pub fn plant(&mut self, row: usize, col: usize, crop: CropType) -> Result<(), GameError> {
+        // 检查瓦片是否可以种植
+        self.check_buildable(row, col)?;
+        if !self.inventory.remove_seed(crop) {
+            return Err(GameError::NoItemInInventory { item: crop.id().to_string() });
+        }
+        self.grid[row][col].state = TileState::Planted {
+            crop,
+            timer: 0,
+            fertilizer: FertilizerType::None,
+            harvests: 0,
+        };
+        Ok(())
+    }
+
+    // 收获作物，返回收获的作物以及与相邻同科成熟作物的杂交结果
+    pub fn harvest(&mut self, row: usize, col: usize) -> Result<HarvestResult, GameError> {
+        // 只有成熟的作物可以收获
+        let TileState::Mature { crop, harvests } = self.tile(row, col)?.state else {
+            return Err(GameError::NothingToHarvest);
+        };
+        if !self.inventory.add_crop(crop, self.ticks) {
+            return Err(GameError::StorageFull);
+        }
+        let neighbors = self.mature_neighbors(row, col);
+        self.codex.discover(crop);
+        let harvests = harvests + 1;
+        // 多季作物在达到收获次数上限前重新进入再生阶段，否则地块清空
+        self.grid[row][col].state = if harvests < crop.max_harvests() {
+            TileState::Planted {
+                crop,
+                timer: 0,
+                fertilizer: FertilizerType::None,
+                harvests,
+            }
+        } else {
+            TileState::Empty
+        };
+
+        // 与相邻同科成熟作物杂交，成功时获得高品级种子
+        let bonus = if self.structure_coverage(StructureType::Beehive)[row][col] {
+            structure::BEEHIVE_BREED_MULTIPLIER
+        } else {
+            1.0
+        };
+        let outcome = crate::rng::with_rng(|rng| breeding::try_breed(crop, &neighbors, bonus, rng));
+        if let Some(outcome) = outcome {
+            if !self.inventory.add_seed(outcome.seed()) {
+                show_message(t("message.bred_seed_dropped"));
             }
         }
+        Ok(HarvestResult { crop, breeding: outcome })
     }
 
     // 随机产生虫害，每帧 2% 概率变成虫害
-    pub fn random_infest(&mut self) {
-        let mut rng = rand::thread_rng();
-        // 遍历网格中的每一行
-        for row in &mut self.grid {
-            // 遍历当前行中的每个瓦片
-            for tile in row {
-                // 如果当前瓦片处于种植状态，则产生虫害
-                if let TileState::Planted { crop, .. } = tile.state {
-                    // 随机值 0.0 ~ 1.0
-                    let chance: f32 = rng.gen();
-                    // 每帧 2% 概率变成虫害
-                    if chance < 0.02 {
-                        // 将作物状态改为虫害
-                        tile.state = TileState::Infested { crop };
-                        crate::utils::show_message("⚠️ 有作物遭遇虫害了！");
+    pub fn random_infest(&mut self) -> Vec<CropType> {
+        crate::rng::with_rng(|rng| {
+            let mut infested = Vec::new();
+            // 稻草人范围内的作物虫害概率降低
+            let guarded = self.structure_coverage(StructureType::Scarecrow);
+            // 遍历网格中的每一行
+            for (r, row) in self.grid.iter_mut().enumerate() {
+                // 遍历当前行中的每个瓦片
+                for (c, tile) in row.iter_mut().enumerate() {
+                    // 如果当前瓦片处于种植状态，则产生虫害
+                    if let TileState::Planted { crop, harvests, .. } = tile.state {
+                        // 随机值 0.0 ~ 1.0
+                        let chance: f32 = rng.gen();
+                        // 每帧 2% 概率变成虫害
+                        let threshold = if guarded[r][c] {
+                            PEST_CHANCE * structure::SCARECROW_PEST_MULTIPLIER
+                        } else {
+                            PEST_CHANCE
+                        };
+                        if chance < threshold {
+                            // 将作物状态改为虫害
+                            tile.state = TileState::Infested { crop, harvests };
+                            show_message(t("message.pests_appeared"));
+                            infested.push(crop);
+                        }
                     }
                 }
             }
+            infested
+        })
+    }
+
+    // 施肥
+    pub fn fertilize(&mut self, row: usize, col: usize, fertilizer: FertilizerType) -> Result<(), GameError> {
+        // 检查瓦片是否可以施肥
+        match self.tile(row, col)?.state {
+            TileState::Planted { fertilizer: FertilizerType::None, .. } => {}
+            TileState::Planted { .. } => return Err(GameError::AlreadyFertilized),
+            _ => return Err(GameError::NotFertilizable),
+        }
+        if !self.inventory.remove_fertilizer(fertilizer) {
+            return Err(GameError::NoItemInInventory { item: fertilizer.id().to_string() });
         }
+        self.grid[row][col].apply_fertilizer(fertilizer);
+        Ok(())
     }
 
-    // 施肥，如果成功施肥则返回 true
-    pub fn fertilize(&mut self, row: usize, col: usize, fertilizer_type: &str) -> bool {
-        // 检查坐标是否在网格范围内
-        if row < self.grid.len() && col < self.grid[0].len() {
-            // 获取指定位置的瓦片
-            let tile = &mut self.grid[row][col];
-            // 获取肥料类型
-            let fertilizer = match fertilizer_type {
-                "basic_fertilizer" => FertilizerType::Basic,
-                "premium_fertilizer" => FertilizerType::Premium,
-                "super_fertilizer" => FertilizerType::Super,
-                _ => FertilizerType::None,
-            };
-            // 检查瓦片是否可以施肥
-            if tile.can_fertilize() && self.inventory.remove_fertilizer(fertilizer_type) {
-                // 施肥
-                return tile.apply_fertilizer(fertilizer);
+    // 把两个角的坐标整理为网格范围内的行列区间
+    pub fn rect_tiles(&self, r0: usize, c0: usize, r1: usize, c1: usize) -> Vec<(usize, usize)> {
+        let max_row = self.grid.len() - 1;
+        let max_col = self.grid[0].len() - 1;
+        let rows = r0.min(r1).min(max_row)..=r0.max(r1).min(max_row);
+        let cols = c0.min(c1).min(max_col)..=c0.max(c1).min(max_col);
+        rows.flat_map(|r| cols.clone().map(move |c| (r, c))).collect()
+    }
+
+    // 检查地块是否为可耕种的土壤
+    fn soil_check(&self, row: usize, col: usize) -> Result<(), SkipReason> {
+        match self.grid[row][col].content {
+            TileContent::Soil => Ok(()),
+            TileContent::Locked => Err(SkipReason::Locked),
+            _ => Err(SkipReason::Building),
+        }
+    }
+
+    // 收获所有成熟的作物
+    pub fn harvest_all(&mut self) -> (Vec<HarvestResult>, AreaSummary) {
+        let mut results = Vec::new();
+        let mut summary = AreaSummary::default();
+        for row in 0..self.grid.len() {
+            for col in 0..self.grid[0].len() {
+                if !self.grid[row][col].can_harvest() {
+                    continue;
+                }
+                match self.harvest(row, col) {
+                    Ok(result) => {
+                        results.push(result);
+                        summary.succeeded += 1;
+                    }
+                    Err(err) => summary.skip(SkipReason::from_error(&err)),
+                }
             }
         }
-        false
+        (results, summary)
     }
 
-    // 获取作物信息，如果坐标无效则返回错误信息
-    pub fn get_crop_info(&self, row: usize, col: usize) -> String {
-        // 检查坐标是否在网格范围内
-        if row < self.grid.len() && col < self.grid[0].len() {
-            // 获取指定位置的瓦片
-            let tile = &self.grid[row][col];
-            // 直接返回 tile 的信息文本
-            tile.get_crop_info()
-        } else {
-            "无效位置".to_string()
+    // 在矩形区域内种植作物，种子用完后剩余地块记为种子不足
+    pub fn plant_rect(&mut self, r0: usize, c0: usize, r1: usize, c1: usize, crop: CropType) -> AreaSummary {
+        let mut summary = AreaSummary::default();
+        for (row, col) in self.rect_tiles(r0, c0, r1, c1) {
+            let result = self
+                .soil_check(row, col)
+                .and_then(|_| self.plant(row, col, crop).map_err(|err| SkipReason::from_error(&err)));
+            match result {
+                Ok(()) => summary.succeeded += 1,
+                Err(reason) => summary.skip(reason),
+            }
+        }
+        summary
+    }
+
+    // 在矩形区域内施肥，返回施过肥的作物
+    pub fn fertilize_rect(&mut self, r0: usize, c0: usize, r1: usize, c1: usize, fertilizer: FertilizerType) -> (Vec<CropType>, AreaSummary) {
+        let mut fertilized = Vec::new();
+        let mut summary = AreaSummary::default();
+        for (row, col) in self.rect_tiles(r0, c0, r1, c1) {
+            let result = self.soil_check(row, col).and_then(|_| {
+                let crop = self.grid[row][col].state.crop().ok_or(SkipReason::NoCrop)?;
+                match self.fertilize(row, col, fertilizer) {
+                    Ok(()) => Ok(crop),
+                    Err(err) => Err(SkipReason::from_error(&err)),
+                }
+            });
+            match result {
+                Ok(crop) => {
+                    fertilized.push(crop);
+                    summary.succeeded += 1;
+                }
+                Err(reason) => summary.skip(reason),
+            }
+        }
+        (fertilized, summary)
+    }
+
+    // 清除所有地块上的害虫，返回清除的数量和失败的原因
+    pub fn spray_all_infested(&mut self) -> AreaSummary {
+        let mut summary = AreaSummary::default();
+        for row in 0..self.grid.len() {
+            for col in 0..self.grid[0].len() {
+                if !matches!(self.grid[row][col].state, TileState::Infested { .. }) {
+                    continue;
+                }
+                match self.spray(row, col) {
+                    Ok(()) => summary.succeeded += 1,
+                    Err(err) => summary.skip(SkipReason::from_error(&err)),
+                }
+            }
+        }
+        summary
+    }
+
+    // 清除地块上的害虫，作物重新开始生长
+    pub fn spray(&mut self, row: usize, col: usize) -> Result<(), GameError> {
+        let tile = self.tile_mut(row, col)?;
+        let TileState::Infested { crop, harvests } = tile.state else {
+            return Err(GameError::NoPests);
+        };
+        tile.state = TileState::Planted {
+            crop,
+            timer: 0,
+            fertilizer: FertilizerType::None,
+            harvests,
+        };
+        Ok(())
+    }
+
+    // 已领工资的帮工各自在负责区域内处理一块地，每个 tick 每人最多处理一块，
+    // 处理过地块的帮工不再闲置，下一天照常领工资
+    pub fn tick_workers(&mut self) -> Vec<WorkResult> {
+        let mut results = Vec::new();
+        let workers = self
+            .crew
+            .workers
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.paid)
+            .map(|(index, w)| (index, w.role, w.area))
+            .collect::<Vec<_>>();
+        for (index, role, area) in workers {
+            for (row, col) in self.rect_tiles(area.r0, area.c0, area.r1, area.c1) {
+                let result = match role {
+                    WorkerRole::Harvester => self.harvest(row, col).map(WorkResult::Harvested),
+                    WorkerRole::Planter { crop } => self
+                        .plant(row, col, crop)
+                        .map(|_| WorkResult::Planted(crop)),
+                    WorkerRole::PestControl => self.spray(row, col).map(|_| WorkResult::Sprayed),
+                };
+                if let Ok(result) = result {
+                    results.push(result);
+                    self.crew.workers[index].idle = false;
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    // 获取作物信息
+    pub fn get_crop_info(&self, row: usize, col: usize) -> Result<String, GameError> {
+        self.tile(row, col).map(|tile| tile.get_crop_info())
+    }
+
+    // 获取所有地块和库存数量，金币由商店提供
+    pub fn snapshot(&self, balance: u32) -> FarmSnapshot {
+        FarmSnapshot {
+            rows: self.grid.len(),
+            cols: self.grid[0].len(),
+            tiles: self.grid.iter().flatten().map(Tile::snapshot).collect(),
+            balance,
+            seeds: self.inventory.seeds.clone(),
+            crops: self.inventory.crops(),
+            fertilizers: self.inventory.fertilizers.clone(),
+            goods: self.inventory.goods.clone(),
+            buildings: self.inventory.buildings.clone(),
+            items: self.inventory.items.clone(),
+        }
+    }
+
+    // 获取地块的结构化信息，补充周围建筑的效果和当前的虫害概率
+    pub fn tile_info(&self, row: usize, col: usize, bug_net: bool) -> Result<TileInfo, GameError> {
+        let mut info = self.tile(row, col)?.info();
+        if !matches!(info.state, TileStatus::Empty | TileStatus::Planted | TileStatus::Mature | TileStatus::Infested) {
+            return Ok(info);
+        }
+        for (kind, modifier) in [
+            (StructureType::Sprinkler, TileModifier::Sprinkler),
+            (StructureType::Scarecrow, TileModifier::Scarecrow),
+            (StructureType::Beehive, TileModifier::Beehive),
+        ] {
+            if self.structure_coverage(kind)[row][col] {
+                info.modifiers.push(modifier);
+            }
+        }
+        if bug_net {
+            info.modifiers.push(TileModifier::BugNet);
+        }
+        if info.state == TileStatus::Planted && !bug_net {
+            info.pest_chance = if info.modifiers.contains(&TileModifier::Scarecrow) {
+                PEST_CHANCE * structure::SCARECROW_PEST_MULTIPLIER
+            } else {
+                PEST_CHANCE
+            };
         }
+        Ok(info)
     }
 
     // 获取完整库存，返回种子、肥料和作物
-    pub fn get_full_inventory(&self) -> (std::collections::HashMap<String, u32>, std::collections::HashMap<String, u32>, std::collections::HashMap<String, u32>) {
+    pub fn get_full_inventory(&self) -> (HashMap<ItemId, u32>, HashMap<ItemId, u32>, HashMap<ItemId, u32>) {
         self.inventory.get_all_items()
     }
 
     // 获取库存，返回种子和肥料
-    pub fn get_inventory(&self) -> (std::collections::HashMap<String, u32>, std::collections::HashMap<String, u32>) {
+    pub fn get_inventory(&self) -> (HashMap<ItemId, u32>, HashMap<ItemId, u32>) {
         // 获取库存
         self.inventory.get_items()
     }
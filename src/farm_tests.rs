@@ -0,0 +1,282 @@
+// 直接操作 Farm 和 Inventory 的单元测试，不经过导出函数
+use crate::farm::Farm;
+use crate::item::ItemId;
+use crate::structure::StructureType;
+use crate::farm::SkipReason;
+use crate::inventory::{Pool, BACKPACK_CAPACITY, BASE_STORAGE_CAPACITY};
+use crate::error::GameError;
+use crate::tile::{CropType, FertilizerType, TileContent, TileState};
+use crate::worker::{Area, WorkerRole};
+
+// 原生环境没有浏览器，关闭消息和音效
+fn new_farm() -> Farm {
+    crate::utils::set_muted(true);
+    crate::rng::reseed(7, 0);
+    Farm::new(10, 10)
+}
+
+// 在 (row, col) 种下一株作物
+fn plant(farm: &mut Farm, row: usize, col: usize, crop: CropType) {
+    farm.inventory.add_seed(crop);
+    assert!(farm.plant(row, col, crop).is_ok());
+}
+
+#[test]
+fn crops_lose_freshness_on_both_tick_paths() {
+    let mut farm = new_farm();
+    farm.inventory.add_crop(CropType::Wheat, 0);
+    for _ in 0..60 {
+        farm.tick();
+    }
+    let after_tick = farm.inventory.oldest_freshness(CropType::Wheat).unwrap();
+    assert!(after_tick < 1.0);
+
+    for _ in 0..60 {
+        farm.tick_without_infestation();
+    }
+    assert!(farm.inventory.oldest_freshness(CropType::Wheat).unwrap() < after_tick);
+}
+
+#[test]
+fn storage_buildings_slow_spoilage() {
+    let mut plain = new_farm();
+    let mut with_barn = new_farm();
+    with_barn.inventory.add_building(ItemId::Structure(StructureType::Barn));
+    assert!(with_barn.place_structure(0, 0, "barn").is_ok());
+    for farm in [&mut plain, &mut with_barn] {
+        farm.inventory.add_crop(CropType::Strawberry, 0);
+        for _ in 0..60 {
+            farm.tick_without_infestation();
+        }
+    }
+    let plain = plain.inventory.oldest_freshness(CropType::Strawberry).unwrap();
+    let with_barn = with_barn.inventory.oldest_freshness(CropType::Strawberry).unwrap();
+    assert!(plain < with_barn && with_barn < 1.0);
+}
+
+#[test]
+fn bug_net_path_uses_growth_time() {
+    let mut farm = new_farm();
+    plant(&mut farm, 0, 0, CropType::Corn);
+    for _ in 1..CropType::Corn.base_growth_time() {
+        farm.tick_without_infestation();
+    }
+    assert!(matches!(farm.grid[0][0].state, TileState::Planted { .. }));
+    farm.tick_without_infestation();
+    assert!(matches!(farm.grid[0][0].state, TileState::Mature { .. }));
+}
+
+#[test]
+fn crop_counts_come_from_harvest_batches() {
+    let mut farm = new_farm();
+    farm.inventory.add_crop(CropType::Wheat, 0);
+    farm.inventory.add_crop(CropType::Wheat, 0);
+    farm.inventory.add_crop(CropType::Wheat, 5);
+    assert!(farm.inventory.crop_count(CropType::Wheat) == 3);
+    assert!(farm.inventory.crops().get(&ItemId::Crop(CropType::Wheat)) == Some(&3));
+
+    // 先卖出最早收获的作物
+    farm.inventory.age_crops(100.0);
+    let oldest = farm.inventory.oldest_freshness(CropType::Wheat).unwrap();
+    assert!(farm.inventory.take_crop(CropType::Wheat) == Some(oldest));
+    assert!(farm.inventory.remove_crops(CropType::Wheat, 2));
+    assert!(farm.inventory.crop_count(CropType::Wheat) == 0);
+    assert!(farm.inventory.crop_batches.is_empty());
+}
+
+#[test]
+fn tile_info_matches_the_shop_price() {
+    let mut farm = new_farm();
+    let mut shop = crate::shop::Shop::new();
+    for (col, crop) in [CropType::GoldenWheat, CropType::PremiumCorn].into_iter().enumerate() {
+        plant(&mut farm, 0, col, crop);
+        assert!(farm.grid[0][col].info().expected_value == shop.sell_crop(crop, 1.0));
+    }
+}
+
+#[test]
+fn plant_rect_reports_skip_reasons() {
+    let mut farm = new_farm();
+    farm.grid[0][0].content = TileContent::Locked;
+    // 谷仓占据 (0, 1) 开始的 2x2 地块
+    farm.inventory.add_building(ItemId::Structure(StructureType::Barn));
+    assert!(farm.place_structure(0, 1, "barn").is_ok());
+    plant(&mut farm, 0, 3, CropType::Wheat);
+    // 只剩一颗种子，最后一块空地因为缺少种子被跳过
+    farm.inventory.add_seed(CropType::Wheat);
+
+    let summary = farm.plant_rect(0, 0, 0, 5, CropType::Wheat);
+    assert!(summary.succeeded == 1);
+    let reasons = summary.skipped.iter().map(|s| (s.reason, s.count)).collect::<Vec<_>>();
+    assert!(reasons == [
+        (SkipReason::Locked, 1),
+        (SkipReason::Building, 2),
+        (SkipReason::Occupied, 1),
+        (SkipReason::OutOfSeeds, 1),
+    ]);
+    assert!(matches!(farm.grid[0][4].state, TileState::Planted { .. }));
+    assert!(matches!(farm.grid[0][5].state, TileState::Empty));
+}
+
+#[test]
+fn plant_rect_stops_planting_when_seeds_run_out() {
+    let mut farm = new_farm();
+    for _ in 0..3 {
+        farm.inventory.add_seed(CropType::Corn);
+    }
+    let summary = farm.plant_rect(0, 0, 1, 4, CropType::Corn);
+    assert!(summary.succeeded == 3);
+    assert!(summary.skipped.len() == 1 && summary.skipped[0].reason == SkipReason::OutOfSeeds);
+    assert!(summary.skipped[0].count == 7);
+    assert!(farm.inventory.seeds.is_empty());
+}
+
+#[test]
+fn area_actions_report_why_tiles_failed() {
+    let mut farm = new_farm();
+    plant(&mut farm, 0, 0, CropType::Wheat);
+    plant(&mut farm, 0, 1, CropType::Wheat);
+    farm.inventory.add_fertilizer(FertilizerType::Basic);
+    let (_, summary) = farm.fertilize_rect(0, 0, 0, 1, FertilizerType::Basic);
+    let reasons = summary.skipped.iter().map(|s| (s.reason, s.count)).collect::<Vec<_>>();
+    assert!(summary.succeeded == 1 && reasons == [(SkipReason::OutOfFertilizer, 1)]);
+    let (_, summary) = farm.fertilize_rect(0, 0, 0, 0, FertilizerType::Basic);
+    assert!(summary.skipped[0].reason == SkipReason::NotFertilizable);
+
+    farm.grid[0][0].state = TileState::Infested { crop: CropType::Wheat, harvests: 0 };
+    let summary = farm.spray_all_infested();
+    assert!(summary.succeeded == 1 && summary.skipped.is_empty());
+
+    // 仓库已满时成熟的作物留在地里
+    farm.grid[0][1].state = TileState::Mature { crop: CropType::Wheat, harvests: 0 };
+    while farm.inventory.add_crop(CropType::Corn, 0) {}
+    let (_, summary) = farm.harvest_all();
+    assert!(summary.succeeded == 0 && summary.skipped[0].reason == SkipReason::StorageFull);
+}
+
+#[test]
+fn skip_reasons_follow_the_tile_error() {
+    let seeds = GameError::NoItemInInventory { item: "wheat".to_string() };
+    let fertilizer = GameError::NoItemInInventory { item: "basic_fertilizer".to_string() };
+    assert!(SkipReason::from_error(&seeds) == SkipReason::OutOfSeeds);
+    assert!(SkipReason::from_error(&fertilizer) == SkipReason::OutOfFertilizer);
+    assert!(SkipReason::from_error(&GameError::TileLocked) == SkipReason::Locked);
+    assert!(SkipReason::from_error(&GameError::NothingToHarvest) == SkipReason::NotReady);
+    assert!(SkipReason::from_error(&GameError::OutOfBounds { row: 10, col: 0 }) == SkipReason::Failed);
+}
+
+#[test]
+fn backpack_holds_seeds_and_fertilizer_up_to_its_capacity() {
+    let mut farm = new_farm();
+    for _ in 0..BACKPACK_CAPACITY - 1 {
+        assert!(farm.inventory.add_seed(CropType::Wheat));
+    }
+    assert!(farm.inventory.add_fertilizer(FertilizerType::Basic));
+    assert!(farm.inventory.free(Pool::Backpack) == 0);
+    assert!(!farm.inventory.add_seed(CropType::Corn));
+    assert!(!farm.inventory.add_fertilizer(FertilizerType::Basic));
+    assert!(farm.inventory.used(Pool::Backpack) == BACKPACK_CAPACITY);
+}
+
+#[test]
+fn full_storage_blocks_harvests_and_drops_goods() {
+    let mut farm = new_farm();
+    for _ in 0..BASE_STORAGE_CAPACITY {
+        assert!(farm.inventory.add_crop(CropType::Wheat, 0));
+    }
+    farm.grid[0][0].state = TileState::Mature { crop: CropType::Corn, harvests: 0 };
+    assert!(matches!(farm.harvest(0, 0), Err(GameError::StorageFull)));
+    assert!(matches!(farm.grid[0][0].state, TileState::Mature { .. }));
+    let Ok(flour) = ItemId::parse("flour") else {
+        panic!("找不到面粉");
+    };
+    assert!(farm.inventory.add_good(flour, 3) == 3);
+
+    // 卖掉一个作物后可以继续收获
+    assert!(farm.inventory.remove_crop(CropType::Wheat));
+    assert!(farm.harvest(0, 0).is_ok());
+    assert!(farm.inventory.used(Pool::Storage) == BASE_STORAGE_CAPACITY);
+}
+
+#[test]
+fn storage_buildings_raise_capacity() {
+    let mut farm = new_farm();
+    farm.inventory.add_building(ItemId::Structure(StructureType::Silo));
+    assert!(farm.place_structure(0, 0, "silo").is_ok());
+    let capacity = BASE_STORAGE_CAPACITY + StructureType::Silo.storage_bonus();
+    assert!(farm.inventory.capacity(Pool::Storage) == capacity);
+    for _ in 0..capacity {
+        assert!(farm.inventory.add_crop(CropType::Wheat, 0));
+    }
+    assert!(!farm.inventory.add_crop(CropType::Wheat, 0));
+
+    // 拆除后放不下现有的作物时不能拆除
+    assert!(matches!(farm.remove_structure(0, 0), Err(GameError::StorageInUse)));
+    assert!(farm.inventory.remove_crops(CropType::Wheat, capacity - BASE_STORAGE_CAPACITY));
+    assert!(matches!(farm.remove_structure(0, 0), Ok(true)));
+    assert!(farm.inventory.capacity(Pool::Storage) == BASE_STORAGE_CAPACITY);
+}
+
+#[test]
+fn removing_a_structure_with_stale_capacity_does_not_underflow() {
+    let mut farm = new_farm();
+    farm.inventory.add_building(ItemId::Structure(StructureType::Silo));
+    assert!(farm.place_structure(0, 0, "silo").is_ok());
+    // 容量尚未按建筑重新计算时，例如直接写入的地块内容
+    farm.inventory.storage_capacity = 0;
+    assert!(matches!(farm.remove_structure(0, 0), Ok(true)));
+    assert!(farm.inventory.capacity(Pool::Storage) == BASE_STORAGE_CAPACITY);
+}
+
+#[test]
+fn perennials_regrow_until_the_harvest_cap() {
+    let mut farm = new_farm();
+    let crop = CropType::Strawberry;
+    plant(&mut farm, 0, 0, crop);
+    for _ in 0..crop.base_growth_time() {
+        farm.tick_without_infestation();
+    }
+    for harvest in 1..=crop.max_harvests() {
+        assert!(matches!(farm.grid[0][0].state, TileState::Mature { .. }));
+        assert!(farm.harvest(0, 0).is_ok());
+        if harvest == crop.max_harvests() {
+            break;
+        }
+        // 再生阶段使用更短的再生时间
+        assert!(matches!(farm.grid[0][0].state, TileState::Planted { harvests, .. } if harvests == harvest));
+        for _ in 1..crop.regrowth_time() {
+            farm.tick_without_infestation();
+        }
+        assert!(matches!(farm.grid[0][0].state, TileState::Planted { .. }));
+        farm.tick_without_infestation();
+    }
+    // 达到收获次数上限后地块清空
+    assert!(matches!(farm.grid[0][0].state, TileState::Empty));
+    assert!(farm.inventory.crop_count(crop) == crop.max_harvests());
+}
+
+#[test]
+fn workers_handle_one_tile_per_tick_and_idle_days_carry_the_wage() {
+    let mut farm = new_farm();
+    let crop = CropType::Wheat;
+    let wage = WorkerRole::Planter { crop }.daily_wage();
+    assert!(farm.crew.hire(WorkerRole::Planter { crop }, Area { r0: 0, c0: 0, r1: 0, c1: 2 }).is_some());
+
+    // 没有种子时一整天闲置，已付的工资顺延到下一天
+    assert!(farm.tick_workers().is_empty());
+    assert!(farm.crew.wages_due() == [None]);
+    farm.crew.start_day(&[true]);
+
+    farm.inventory.add_seed(crop);
+    farm.inventory.add_seed(crop);
+    assert!(farm.tick_workers().len() == 1);
+    assert!(matches!(farm.grid[0][0].state, TileState::Planted { .. }));
+    assert!(matches!(farm.grid[0][1].state, TileState::Empty));
+    assert!(farm.crew.wages_due() == [Some(wage)]);
+
+    // 未付清工资的帮工停工，下一天仍要收取工资
+    farm.crew.start_day(&[false]);
+    assert!(farm.tick_workers().is_empty());
+    assert!(matches!(farm.grid[0][1].state, TileState::Empty));
+    assert!(farm.crew.wages_due() == [Some(wage)]);
+}
@@ -0,0 +1,143 @@
+// 在原生环境下用随机坐标执行所有地块相关的操作，越界坐标只能返回错误，不能崩溃
+// 原生环境无法把错误转换为 JsValue，可能失败的操作直接通过 execute 执行
+use super::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// 原生环境没有浏览器，关闭消息和音效，并像回放一样跳过存档
+pub(crate) fn headless_game() {
+    crate::utils::set_muted(true);
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    reset_game(7);
+    SHOP.with(|shop| shop.borrow_mut().balance = 1_000_000);
+    FARM.with(|farm| farm.borrow_mut().add_xp(1_000_000));
+}
+
+// 随机坐标，包括网格内、刚好越界和拖拽到画布外时负数转换得到的坐标
+fn coord(rng: &mut StdRng) -> usize {
+    match rng.gen_range(0..10) {
+        0 => usize::MAX,
+        1 => -1i32 as usize,
+        2 => rng.gen(),
+        _ => rng.gen_range(0..14),
+    }
+}
+
+#[test]
+fn out_of_bounds_tiles_report_an_error() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    let outside = GameError::OutOfBounds { row: 10, col: 3 };
+
+    let plant_outside = Command::Plant { row: 10, col: 3, crop: "wheat".to_string() };
+    assert!(execute(plant_outside).err() == Some(outside.clone()));
+    assert!(tile_state(10, 3) == Err(outside.clone()));
+    assert!(tile_info(10, 3) == Err(outside.clone()));
+    assert!(FARM.with(|farm| farm.borrow().get_crop_info(10, 3)) == Err(outside.clone()));
+    assert!(tooltip_text(10, 3).is_none() && tooltip_text(0, 3).is_some());
+    assert!(execute(Command::Fertilize { row: 10, col: 3, fertilizer: None }).err() == Some(outside));
+
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    assert!(tile_state(0, 0) == Ok("planted_wheat".to_string()));
+
+    let Ok(info) = tile_info(0, 0) else { panic!("地块 (0, 0) 应在网格范围内") };
+    assert!(info.state == crate::tile::TileStatus::Planted);
+    assert!(info.crop == Some("wheat") && info.fertilizer.is_none());
+    assert!(info.total_seconds == 10 && info.remaining_seconds == 10);
+    assert!(info.expected_value == 15 && info.pest_chance > 0.0);
+}
+
+#[test]
+fn random_coordinates_never_panic() {
+    headless_game();
+    let mut rng = StdRng::seed_from_u64(2024);
+    for _ in 0..20 {
+        assert!(buy_seed("wheat".to_string()).is_ok());
+        assert!(buy_fertilizer("basic_fertilizer".to_string()).is_ok());
+    }
+    assert!(buy_machine("mill".to_string()).is_ok());
+    assert!(buy_structure("barn".to_string()).is_ok());
+    assert!(buy_structure("scarecrow".to_string()).is_ok());
+    assert!(buy_pen("chicken_coop".to_string()).is_ok());
+
+    for step in 0..5000 {
+        if step % 10 == 0 {
+            tick();
+        }
+        let (row, col) = (coord(&mut rng), coord(&mut rng));
+        let command = match rng.gen_range(0..19) {
+            0 => Command::Plant { row, col, crop: "wheat".to_string() },
+            1 => Command::Harvest { row, col },
+            2 => Command::SprayTile { row, col },
+            3 => Command::ClearTile { row, col },
+            4 => Command::Fertilize { row, col, fertilizer: None },
+            5 => Command::PlaceMachine { row, col, machine: "mill".to_string() },
+            6 => Command::PlaceStructure { row, col, structure: "barn".to_string() },
+            7 => Command::PlaceStructure { row, col, structure: "scarecrow".to_string() },
+            8 => Command::PlacePen { row, col, pen: "chicken_coop".to_string() },
+            9 => Command::BuyAnimal { row, col },
+            10 => Command::FeedAnimals { row, col },
+            11 => Command::StartProcessing { row, col, input: None },
+            12 => {
+                drop(tile_state(row, col));
+                continue;
+            }
+            13 => {
+                drop(FARM.with(|farm| farm.borrow().get_crop_info(row, col)));
+                drop(tile_info(row, col));
+                drop(tooltip_text(row, col));
+                continue;
+            }
+            14 => Command::PlantRect { r0: row, c0: col, r1: coord(&mut rng), c1: coord(&mut rng), crop: "wheat".to_string() },
+            15 => Command::FertilizeRect { r0: row, c0: col, r1: coord(&mut rng), c1: coord(&mut rng), fertilizer: None },
+            16 => Command::HireWorker {
+                role: "harvester".to_string(),
+                crop: None,
+                r0: row,
+                c0: col,
+                r1: coord(&mut rng),
+                c1: coord(&mut rng),
+            },
+            17 => Command::Undo,
+            _ => Command::BuySeed { seed: "wheat".to_string() },
+        };
+        drop(execute(command));
+    }
+}
+
+#[test]
+fn snapshot_matches_tile_state() {
+    headless_game();
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(buy_seed("wheat".to_string()).is_ok());
+    assert!(buy_machine("mill".to_string()).is_ok());
+    assert!(buy_pen("chicken_coop".to_string()).is_ok());
+    assert!(plant(0, 0, "wheat".to_string()).is_ok());
+    assert!(plant(0, 1, "wheat".to_string()).is_ok());
+    assert!(place_machine(1, 0, "mill".to_string()).is_ok());
+    assert!(place_pen(1, 1, "chicken_coop".to_string()).is_ok());
+    for _ in 0..12 {
+        tick();
+    }
+
+    let snapshot = farm_snapshot();
+    assert!(snapshot.tiles.len() == snapshot.rows * snapshot.cols);
+    assert!(snapshot.balance == SHOP.with(|shop| shop.borrow().get_balance()));
+    for (index, tile) in snapshot.tiles.iter().enumerate() {
+        let state = tile_state(index / snapshot.cols, index % snapshot.cols).unwrap_or_default();
+        let expected = match tile.state {
+            crate::tile::TileStatus::Locked => "locked".to_string(),
+            crate::tile::TileStatus::Empty => "empty".to_string(),
+            crate::tile::TileStatus::Planted => format!("planted_{}", tile.crop.map(|c| c.id()).unwrap_or_default()),
+            crate::tile::TileStatus::Mature => format!("mature_{}", tile.crop.map(|c| c.id()).unwrap_or_default()),
+            crate::tile::TileStatus::Infested => format!("infested_{}", tile.crop.map(|c| c.id()).unwrap_or_default()),
+            crate::tile::TileStatus::Machine => format!("machine_{}", tile.building.map(|b| b.id()).unwrap_or_default()),
+            crate::tile::TileStatus::Structure => format!("structure_{}", tile.building.map(|b| b.id()).unwrap_or_default()),
+            crate::tile::TileStatus::Pen => match tile.building {
+                Some(ItemId::Pen(animal)) => format!("pen_{}", animal.id()),
+                _ => String::new(),
+            },
+        };
+        assert!(state == expected, "地块 {} 的状态 {} 与快照 {} 不一致", index, state, expected);
+    }
+}
@@ -0,0 +1,158 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Display;
+use serde::{Serialize, Deserialize};
+
+// 界面语言
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+// 语言包：消息键到文本的映射
+pub type Bundle = HashMap<String, String>;
+
+thread_local! {
+    static LOCALE: Cell<Locale> = const { Cell::new(Locale::ZhCn) };
+    // 语言包在第一次使用时解析，之后一直保留
+    static BUNDLES: RefCell<HashMap<Locale, &'static Bundle>> = RefCell::new(HashMap::new());
+    // 找不到翻译的键名，每个键只保留一份，避免每次查找都泄漏内存
+    static MISSING: RefCell<HashMap<String, &'static str>> = RefCell::new(HashMap::new());
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    // 解析语言标签，只看主语言部分，例如 en-GB 也使用英文
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => include_str!("../locales/zh-CN.json"),
+            Locale::EnUs => include_str!("../locales/en-US.json"),
+        }
+    }
+
+    // 数字的千位分隔符，中文界面沿用不分隔的写法
+    fn group_separator(&self) -> Option<char> {
+        match self {
+            Locale::ZhCn => None,
+            Locale::EnUs => Some(','),
+        }
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.with(|l| l.set(locale));
+}
+
+pub fn locale() -> Locale {
+    LOCALE.with(|l| l.get())
+}
+
+// 语言包中的所有消息
+pub fn bundle(locale: Locale) -> &'static Bundle {
+    BUNDLES.with(|bundles| {
+        *bundles.borrow_mut().entry(locale).or_insert_with(|| {
+            let bundle: Bundle = serde_json::from_str(locale.source()).expect("语言包格式错误");
+            Box::leak(Box::new(bundle))
+        })
+    })
+}
+
+// 按当前语言查找消息，缺少翻译时回退到中文，仍然找不到时直接显示键名
+pub fn t(key: &str) -> &'static str {
+    lookup(locale(), key)
+        .or_else(|| lookup(Locale::ZhCn, key))
+        .unwrap_or_else(|| missing(key))
+}
+
+// 缺少翻译时显示的键名
+fn missing(key: &str) -> &'static str {
+    MISSING.with(|missing| {
+        let mut missing = missing.borrow_mut();
+        if let Some(text) = missing.get(key) {
+            return *text;
+        }
+        let text: &'static str = Box::leak(key.to_string().into_boxed_str());
+        missing.insert(key.to_string(), text);
+        text
+    })
+}
+
+// 查找消息，不做回退
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    bundle(locale).get(key).map(|text| text.as_str())
+}
+
+// 查找消息并把其中的 {name} 替换为同名参数
+// 英文等有单复数的语言中，count 为 1 时优先使用 "<key>.one"
+pub fn format(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let singular = args.iter().any(|(name, value)| *name == "count" && value.to_string() == "1");
+    let template = singular
+        .then(|| lookup(locale(), &format!("{}.one", key)))
+        .flatten()
+        .unwrap_or_else(|| t(key));
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let name = &rest[start + 1..start + len];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => text.push_str(&value.to_string()),
+            None => text.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+    text
+}
+
+// 按当前语言的习惯格式化整数，例如英文显示为 12,345
+pub fn number(n: impl Into<u64>) -> String {
+    let digits = n.into().to_string();
+    let Some(separator) = locale().group_separator() else {
+        return digits;
+    };
+    let mut text = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            text.push(separator);
+        }
+        text.push(digit);
+    }
+    text
+}
+
+// 用顿号或逗号连接多个短语
+pub fn join(parts: &[String]) -> String {
+    parts.join(t("common.list_separator"))
+}
+
+// 查找消息并替换参数：tf!("key", name = value, ...)
+macro_rules! tf {
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::format($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+    };
+}
+pub(crate) use tf;
@@ -1,12 +1,59 @@
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
+use crate::item::ItemId;
+use crate::tile::{CropType, FertilizerType};
+
+// 背包可以存放的种子和肥料总数
+pub const BACKPACK_CAPACITY: u32 = 100;
+// 没有储存建筑时仓库可以存放的作物和加工品总数
+pub const BASE_STORAGE_CAPACITY: u32 = 50;
+
+// 表示库存中的存放位置
+#[derive(Clone, Copy)]
+pub enum Pool {
+    Backpack, // 种子和肥料
+    Storage,  // 作物和加工品，储存建筑可以扩容
+}
+
+// 作物完全不新鲜时的售价比例
+pub const MIN_FRESHNESS: f32 = 0.25;
+
+// 同一时刻收获的一批作物
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CropBatch {
+    pub harvested_at: u64, // 收获时农场经历的时间流逝次数
+    pub count: u32,
+    pub freshness: f32,    // 新鲜度，1.0 为刚收获，决定售价比例
+}
+
+fn base_storage_capacity() -> u32 {
+    BASE_STORAGE_CAPACITY
+}
+
+// 提供给界面的容量信息
+#[derive(Serialize)]
+pub struct CapacityInfo {
+    pub backpack_used: u32,
+    pub backpack_capacity: u32,
+    pub storage_used: u32,
+    pub storage_capacity: u32,
+}
 
 // 表示一个库存，包含种子、作物和肥料
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Inventory {
-    pub seeds: HashMap<String, u32>,  // 种子，键为种子名称，值为数量
-    pub crops: HashMap<String, u32>,  // 作物，键为作物名称，值为数量
-    pub fertilizers: HashMap<String, u32>,
+    pub seeds: HashMap<ItemId, u32>,  // 种子，键为对应的作物，值为数量
+    pub fertilizers: HashMap<ItemId, u32>,
+    #[serde(default)]
+    pub goods: HashMap<ItemId, u32>,      // 加工品，值为数量
+    #[serde(default)]
+    pub buildings: HashMap<ItemId, u32>,  // 已购买但尚未放置的建筑
+    #[serde(default)]
+    pub items: HashMap<ItemId, u32>,      // 通过奖励获得的工具和装饰品
+    #[serde(default)]
+    pub crop_batches: HashMap<ItemId, Vec<CropBatch>>, // 作物，每种作物按收获时间排列的批次，作物数量由批次相加得到
+    #[serde(skip, default = "base_storage_capacity")]
+    pub storage_capacity: u32,            // 仓库容量，由农场根据储存建筑计算
 }
 
 impl Inventory {
@@ -14,75 +61,216 @@ impl Inventory {
     pub fn new() -> Self {
         Self {
             seeds: HashMap::new(),
-            crops: HashMap::new(),
             fertilizers: HashMap::new(),
+            goods: HashMap::new(),
+            buildings: HashMap::new(),
+            items: HashMap::new(),
+            crop_batches: HashMap::new(),
+            storage_capacity: BASE_STORAGE_CAPACITY,
+        }
+    }
+
+    // 已使用的容量
+    pub fn used(&self, pool: Pool) -> u32 {
+        match pool {
+            Pool::Backpack => self.seeds.values().chain(self.fertilizers.values()).sum(),
+            Pool::Storage => self.crop_batches.values().flatten().map(|b| b.count).chain(self.goods.values().copied()).sum(),
+        }
+    }
+
+    pub fn capacity(&self, pool: Pool) -> u32 {
+        match pool {
+            Pool::Backpack => BACKPACK_CAPACITY,
+            Pool::Storage => self.storage_capacity,
         }
     }
 
-    // 添加种子，如果种子不存在则创建
-    pub fn add_seed(&mut self, seed: &str) {
-        *self.seeds.entry(seed.to_string()).or_insert(0) += 1;
+    // 剩余容量，超出容量时为 0
+    pub fn free(&self, pool: Pool) -> u32 {
+        self.capacity(pool).saturating_sub(self.used(pool))
+    }
+
+    pub fn capacity_info(&self) -> CapacityInfo {
+        CapacityInfo {
+            backpack_used: self.used(Pool::Backpack),
+            backpack_capacity: self.capacity(Pool::Backpack),
+            storage_used: self.used(Pool::Storage),
+            storage_capacity: self.capacity(Pool::Storage),
+        }
+    }
+
+    // 添加种子，如果种子不存在则创建，背包已满时返回 false
+    pub fn add_seed(&mut self, seed: CropType) -> bool {
+        if self.free(Pool::Backpack) == 0 {
+            return false;
+        }
+        *self.seeds.entry(ItemId::Crop(seed)).or_insert(0) += 1;
+        true
+    }
+
+    // 添加在 tick 时收获的作物，如果作物不存在则创建，仓库已满时返回 false
+    pub fn add_crop(&mut self, crop: CropType, tick: u64) -> bool {
+        if self.free(Pool::Storage) == 0 {
+            return false;
+        }
+        let batches = self.crop_batches.entry(ItemId::Crop(crop)).or_default();
+        match batches.last_mut() {
+            Some(batch) if batch.harvested_at == tick => batch.count += 1,
+            _ => batches.push(CropBatch { harvested_at: tick, count: 1, freshness: 1.0 }),
+        }
+        true
+    }
+
+    // 添加肥料，如果肥料不存在则创建，背包已满时返回 false
+    pub fn add_fertilizer(&mut self, fertilizer: FertilizerType) -> bool {
+        if self.free(Pool::Backpack) == 0 {
+            return false;
+        }
+        *self.fertilizers.entry(ItemId::Fertilizer(fertilizer)).or_insert(0) += 1;
+        true
+    }
+
+    // 添加加工品，如果加工品不存在则创建，仓库放不下的部分被丢弃，返回丢弃的数量
+    pub fn add_good(&mut self, good: ItemId, count: u32) -> u32 {
+        let stored = count.min(self.free(Pool::Storage));
+        if stored > 0 {
+            *self.goods.entry(good).or_insert(0) += stored;
+        }
+        count - stored
     }
 
-    // 添加作物，如果作物不存在则创建
-    pub fn add_crop(&mut self, crop: &str) {
-        *self.crops.entry(crop.to_string()).or_insert(0) += 1;  
+    // 添加建筑，如果建筑不存在则创建
+    pub fn add_building(&mut self, building: ItemId) {
+        *self.buildings.entry(building).or_insert(0) += 1;
     }
 
-    // 添加肥料，如果肥料不存在则创建
-    pub fn add_fertilizer(&mut self, fertilizer: &str) {
-        *self.fertilizers.entry(fertilizer.to_string()).or_insert(0) += 1;
+    // 添加工具或装饰品，如果物品不存在则创建
+    pub fn add_item(&mut self, item: ItemId, count: u32) {
+        *self.items.entry(item).or_insert(0) += count;
     }
 
     // 移除种子，如果种子不存在则返回 false
-    pub fn remove_seed(&mut self, seed: &str) -> bool {
-        if let Some(count) = self.seeds.get_mut(seed) {
-            if *count > 0 {
-                *count -= 1;
-                if *count == 0 {
-                    self.seeds.remove(seed);
-                }
-                return true;
+    pub fn remove_seed(&mut self, seed: CropType) -> bool {
+        remove_one(&mut self.seeds, ItemId::Crop(seed))
+    }
+
+    // 移除作物，如果作物不存在则返回 false
+    pub fn remove_crop(&mut self, crop: CropType) -> bool {
+        self.take_crop(crop).is_some()
+    }
+
+    // 取出最早收获的一个作物，返回它的新鲜度
+    pub fn take_crop(&mut self, crop: CropType) -> Option<f32> {
+        let batches = self.crop_batches.get_mut(&ItemId::Crop(crop))?;
+        let batch = batches.first_mut()?;
+        let freshness = batch.freshness;
+        batch.count -= 1;
+        if batch.count == 0 {
+            batches.remove(0);
+        }
+        if batches.is_empty() {
+            self.crop_batches.remove(&ItemId::Crop(crop));
+        }
+        Some(freshness)
+    }
+
+    // 某种作物的数量
+    pub fn crop_count(&self, crop: CropType) -> u32 {
+        batch_count(self.crop_batches.get(&ItemId::Crop(crop)))
+    }
+
+    // 每种作物的数量
+    pub fn crops(&self) -> HashMap<ItemId, u32> {
+        self.crop_batches.iter().map(|(crop, batches)| (*crop, batch_count(Some(batches)))).collect()
+    }
+
+    // 持有的某种物品的数量，种子和同名作物一起计算
+    pub fn count(&self, item: ItemId) -> u32 {
+        [&self.seeds, &self.fertilizers, &self.goods, &self.buildings, &self.items]
+            .iter()
+            .filter_map(|map| map.get(&item))
+            .sum::<u32>()
+            + batch_count(self.crop_batches.get(&item))
+    }
+
+    // 最早收获的一个作物的新鲜度
+    pub fn oldest_freshness(&self, crop: CropType) -> Option<f32> {
+        self.crop_batches.get(&ItemId::Crop(crop))?.first().map(|b| b.freshness)
+    }
+
+    // 让所有作物变得不那么新鲜，rate 为储存建筑减缓后的腐败速度
+    pub fn age_crops(&mut self, rate: f32) {
+        for (crop, batches) in self.crop_batches.iter_mut() {
+            let shelf_life = match crop {
+                ItemId::Crop(crop) => crop.shelf_life(),
+                _ => u32::MAX,
+            };
+            let decay = rate * (1.0 - MIN_FRESHNESS) / shelf_life as f32;
+            for batch in batches.iter_mut() {
+                batch.freshness = (batch.freshness - decay).max(MIN_FRESHNESS);
             }
         }
-        false
     }
 
-    // 移除作物，如果作物不存在则返回 false
-    pub fn remove_crop(&mut self, crop: &str) -> bool {
-        if let Some(count) = self.crops.get_mut(crop) {
-            if *count > 0 {
-                *count -= 1;
-                if *count == 0 {
-                    self.crops.remove(crop);
-                }
-                return true;
+    // 旧存档只记录了作物数量，没有收获批次的作物视为在 tick 时刚收获
+    pub fn add_legacy_crops(&mut self, counts: HashMap<ItemId, u32>, tick: u64) {
+        for (crop, count) in counts {
+            if count > 0 && !self.crop_batches.contains_key(&crop) {
+                self.crop_batches.insert(crop, vec![CropBatch { harvested_at: tick, count, freshness: 1.0 }]);
             }
         }
-        false
     }
 
     // 移除肥料，如果肥料不存在则返回 false
-    pub fn remove_fertilizer(&mut self, fertilizer: &str) -> bool {
-        if let Some(count) = self.fertilizers.get_mut(fertilizer) {
-            if *count > 0 {
-                *count -= 1;
-                if *count == 0 {
-                    self.fertilizers.remove(fertilizer);
-                }
-                return true;
-            }
+    pub fn remove_fertilizer(&mut self, fertilizer: FertilizerType) -> bool {
+        remove_one(&mut self.fertilizers, ItemId::Fertilizer(fertilizer))
+    }
+
+    // 一次移除多个作物，数量不足时不做任何修改并返回 false
+    pub fn remove_crops(&mut self, crop: CropType, amount: u32) -> bool {
+        if self.crop_count(crop) < amount {
+            return false;
         }
-        false
+        for _ in 0..amount {
+            self.remove_crop(crop);
+        }
+        true
+    }
+
+    // 移除加工品，如果加工品不存在则返回 false
+    pub fn remove_good(&mut self, good: ItemId) -> bool {
+        remove_one(&mut self.goods, good)
+    }
+
+    // 移除建筑，如果建筑不存在则返回 false
+    pub fn remove_building(&mut self, building: ItemId) -> bool {
+        remove_one(&mut self.buildings, building)
     }
 
     // 获取库存，返回种子和作物
-    pub fn get_items(&self) -> (HashMap<String, u32>, HashMap<String, u32>) {
-        (self.seeds.clone(), self.crops.clone())
+    pub fn get_items(&self) -> (HashMap<ItemId, u32>, HashMap<ItemId, u32>) {
+        (self.seeds.clone(), self.crops())
     }
 
     // 获取完整库存，返回种子、作物和肥料
-    pub fn get_all_items(&self) -> (HashMap<String, u32>, HashMap<String, u32>, HashMap<String, u32>) {
-        (self.seeds.clone(), self.crops.clone(), self.fertilizers.clone())
+    pub fn get_all_items(&self) -> (HashMap<ItemId, u32>, HashMap<ItemId, u32>, HashMap<ItemId, u32>) {
+        (self.seeds.clone(), self.crops(), self.fertilizers.clone())
+    }
+}
+
+// 从计数表中移除一个物品，数量减为 0 时删除该项，物品不存在时返回 false
+fn remove_one(counts: &mut HashMap<ItemId, u32>, item: ItemId) -> bool {
+    match counts.get_mut(&item) {
+        Some(count) if *count > 1 => *count -= 1,
+        Some(1) => {
+            counts.remove(&item);
+        }
+        _ => return false,
     }
-}
\ No newline at end of file
+    true
+}
+
+// 各收获批次的作物数量之和
+fn batch_count(batches: Option<&Vec<CropBatch>>) -> u32 {
+    batches.map_or(0, |batches| batches.iter().map(|b| b.count).sum())
+}
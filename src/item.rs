@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize, Deserializer};
+use serde::de::DeserializeOwned;
+use crate::tile::{CropType, FertilizerType};
+use crate::processing::{self, MachineType};
+use crate::structure::StructureType;
+use crate::animal::AnimalType;
+use crate::reward;
+use crate::error::GameError;
+
+// 表示一个已知的物品，种子和作物使用相同的编号
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(into = "String")]
+pub enum ItemId {
+    Crop(CropType),
+    Fertilizer(FertilizerType),
+    Machine(MachineType),
+    Structure(StructureType),
+    Pen(AnimalType),
+    Good(&'static str),
+    Item(&'static str), // 通过奖励获得的工具和装饰品
+}
+
+impl ItemId {
+    pub fn parse(id: &str) -> Result<Self, GameError> {
+        let fertilizer = FertilizerType::from_string(id);
+        CropType::from_id(id)
+            .map(ItemId::Crop)
+            .or((fertilizer != FertilizerType::None).then_some(ItemId::Fertilizer(fertilizer)))
+            .or_else(|| MachineType::from_id(id).map(ItemId::Machine))
+            .or_else(|| StructureType::from_id(id).map(ItemId::Structure))
+            .or_else(|| AnimalType::from_pen_id(id).map(ItemId::Pen))
+            .or_else(|| processing::find_good(id).map(|g| ItemId::Good(g.id)))
+            .or_else(|| reward::find_item(id).map(|i| ItemId::Item(i.id)))
+            .ok_or_else(|| unknown(id, "item"))
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            ItemId::Crop(crop) => crop.id(),
+            ItemId::Fertilizer(fertilizer) => fertilizer.id(),
+            ItemId::Machine(machine) => machine.id(),
+            ItemId::Structure(structure) => structure.id(),
+            ItemId::Pen(animal) => animal.pen_id(),
+            ItemId::Good(id) | ItemId::Item(id) => id,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ItemId::Crop(crop) => crop.display_name(),
+            ItemId::Fertilizer(fertilizer) => fertilizer.display_name(),
+            ItemId::Machine(machine) => machine.display_name(),
+            ItemId::Structure(structure) => structure.display_name(),
+            ItemId::Pen(animal) => animal.pen_display_name(),
+            ItemId::Good(id) => processing::find_good(id).map(|g| g.display_name()).unwrap_or(id),
+            ItemId::Item(id) => reward::find_item(id).map(|i| i.display_name()).unwrap_or(id),
+        }
+    }
+
+    // 是否是可以放置在农场上的建筑
+    pub fn is_building(&self) -> bool {
+        matches!(self, ItemId::Machine(_) | ItemId::Structure(_) | ItemId::Pen(_))
+    }
+}
+
+impl TryFrom<String> for ItemId {
+    type Error = String;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        ItemId::parse(&id).map_err(|e| e.message())
+    }
+}
+
+// 手动实现反序列化，避免 &'static str 字段要求借用 'static 的输入
+impl<'de> Deserialize<'de> for ItemId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        ItemId::try_from(id).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<ItemId> for String {
+    fn from(item: ItemId) -> Self {
+        item.id().to_string()
+    }
+}
+
+// 解析作物或种子编号
+pub fn parse_crop(id: &str) -> Result<CropType, GameError> {
+    CropType::from_id(id).ok_or_else(|| unknown(id, "crop"))
+}
+
+// 解析肥料编号
+pub fn parse_fertilizer(id: &str) -> Result<FertilizerType, GameError> {
+    match FertilizerType::from_string(id) {
+        FertilizerType::None => Err(unknown(id, "fertilizer")),
+        fertilizer => Ok(fertilizer),
+    }
+}
+
+// 解析加工机器编号
+pub fn parse_machine(id: &str) -> Result<MachineType, GameError> {
+    MachineType::from_id(id).ok_or_else(|| unknown(id, "machine"))
+}
+
+// 解析建筑编号
+pub fn parse_structure(id: &str) -> Result<StructureType, GameError> {
+    StructureType::from_id(id).ok_or_else(|| unknown(id, "structure"))
+}
+
+// 解析围栏编号
+pub fn parse_pen(id: &str) -> Result<AnimalType, GameError> {
+    AnimalType::from_pen_id(id).ok_or_else(|| unknown(id, "pen"))
+}
+
+// 解析加工品编号
+pub fn parse_good(id: &str) -> Result<&'static str, GameError> {
+    processing::find_good(id)
+        .map(|g| g.id)
+        .ok_or_else(|| unknown(id, "good"))
+}
+
+fn unknown(id: &str, kind: &'static str) -> GameError {
+    GameError::UnknownItem { id: id.to_string(), kind }
+}
+
+// 读取存档中按物品编号索引的表，跳过已经不存在的物品，避免一个未知编号导致整个存档无法读取
+pub fn skip_unknown_items<'de, D, V>(deserializer: D) -> Result<HashMap<ItemId, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let entries = HashMap::<String, V>::deserialize(deserializer)?;
+    Ok(entries.into_iter().filter_map(|(id, value)| Some((ItemId::parse(&id).ok()?, value))).collect())
+}
+
+// 读取存档中的列表，跳过引用了未知物品等无法读取的条目
+pub fn skip_invalid<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let entries = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries.into_iter().filter_map(|entry| serde_json::from_value(entry).ok()).collect())
+}
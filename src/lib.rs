@@ -18,10 +18,6 @@ use web_sys::{
 use std::cell::RefCell;
 use std::rc::Rc;
 use serde::{Serialize, Deserialize};
-use serde_json;
-use wasm_bindgen_futures::spawn_local;
-use rand::seq::SliceRandom;
-use rand::Rng;
 
 
 // 导入模块和类型
@@ -29,99 +25,156 @@ mod shop;
 mod tile;
 mod inventory;
 mod farm;
-use crate::tile::{CropType, TileState,Tile};
-use crate::inventory::Inventory;
+mod breeding;
+mod seed_maker;
+mod processing;
+mod structure;
+mod animal;
+mod task;
+mod quest;
+mod reward;
+mod progression;
+mod achievement;
+mod statistics;
+mod undo;
+mod command;
+mod rng;
+mod worker;
+mod rules;
+mod item;
+mod error;
+mod i18n;
+#[cfg(test)]
+mod action_tests;
+#[cfg(test)]
+mod breeding_tests;
+#[cfg(test)]
+mod farm_tests;
+#[cfg(test)]
+mod fuzz_tests;
+#[cfg(test)]
+mod locale_tests;
+#[cfg(test)]
+mod rules_tests;
+use crate::breeding::{BreedingOutcome, Codex};
+use crate::seed_maker::SeedMaker;
+use crate::processing::MachineType;
+use crate::animal::AnimalType;
+use crate::task::{Task, TaskEvent};
+use crate::quest::QuestLog;
+use crate::achievement::AchievementTracker;
+use crate::statistics::{StatEvent, Statistics};
+use crate::undo::{ActionKind, Checkpoint, UndoHistory};
+use crate::command::{ActionLog, Command, Outcome};
+use crate::worker::{Area, Crew, WorkResult, WorkerRole};
+use crate::rules::{Rule, RuleBook, RuleEffect};
+use crate::error::GameError;
+use crate::i18n::{t, tf, Locale};
+use crate::item::ItemId;
+use crate::tile::{CropType, TileContent, TileInfo, TileState, TileStatus};
 use crate::shop::Shop;
-use crate::farm::Farm;
+use crate::farm::{AreaSummary, Farm, FarmSnapshot, HarvestResult};
+use crate::inventory::{CropBatch, Pool};
 
 // 表示游戏状态，包含农场网格、库存、余额和任务
+// 物品表和任务读取时跳过已不存在的物品，其余部分引用未知物品时存档读取失败
 #[derive(Serialize, Deserialize)]
 struct GameState {
     farm_grid: Vec<Vec<TileState>>,
-    inventory_seeds: std::collections::HashMap<String, u32>,
-    inventory_crops: std::collections::HashMap<String, u32>,
-    inventory_fertilizers: std::collections::HashMap<String, u32>,
+    #[serde(deserialize_with = "item::skip_unknown_items")]
+    inventory_seeds: std::collections::HashMap<ItemId, u32>,
+    // 旧存档中的作物数量，现在作物由收获批次记录，只在读取旧存档时使用
+    #[serde(default, skip_serializing, deserialize_with = "item::skip_unknown_items")]
+    inventory_crops: std::collections::HashMap<ItemId, u32>,
+    #[serde(deserialize_with = "item::skip_unknown_items")]
+    inventory_fertilizers: std::collections::HashMap<ItemId, u32>,
+    #[serde(default, deserialize_with = "item::skip_unknown_items")]
+    inventory_goods: std::collections::HashMap<ItemId, u32>,
+    #[serde(default, deserialize_with = "item::skip_unknown_items")]
+    inventory_buildings: std::collections::HashMap<ItemId, u32>,
+    #[serde(default, deserialize_with = "item::skip_unknown_items")]
+    inventory_items: std::collections::HashMap<ItemId, u32>,
+    #[serde(default, deserialize_with = "item::skip_unknown_items")]
+    inventory_crop_batches: std::collections::HashMap<ItemId, Vec<CropBatch>>,
+    #[serde(default)]
+    farm_contents: Vec<Vec<TileContent>>,
+    #[serde(default)]
+    ticks: u64,
+    #[serde(default)]
+    xp: u32,
+    #[serde(default = "progression::legacy_level")]
+    level: u32,
     balance: u32,
+    #[serde(deserialize_with = "item::skip_invalid")]
     tasks: Vec<Task>, // 新增字段
+    #[serde(default)]
+    codex: Codex,
+    #[serde(default)]
+    seed_maker: SeedMaker,
+    #[serde(default = "QuestLog::all_completed")]
+    quests: QuestLog,
+    #[serde(default)]
+    statistics: Statistics,
+    // 旧存档中的操作日志，现在日志使用单独的存储键，只在读取旧存档时使用
+    #[serde(default, skip_serializing)]
+    action_log: ActionLog,
+    #[serde(default)]
+    bug_protection: bool,
+    #[serde(default)]
+    crew: Crew,
+    #[serde(default)]
+    rules: RuleBook,
+    #[serde(default = "default_fertilizer")]
+    selected_fertilizer: String,
 }
 
-// 表示任务类型，包含种植作物
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub enum TaskType {
-    PlantCrop { crop: String, count: u32 },
-}
-
-// 表示任务，包含任务类型、进度、目标和奖励
-#[derive(Serialize, Deserialize, Clone)]
-pub struct Task {
-    pub id: u32,
-    pub description: String,
-    pub task_type: TaskType,
-    pub progress: u32,
-    pub target: u32,
-    pub reward: u32,
-    pub completed: bool,
-    pub claimed: bool,
+// 新游戏默认选择的肥料
+fn default_fertilizer() -> String {
+    "basic_fertilizer".to_string()
 }
 
 // 定义线程本地变量，用于存储农场、商店、图片、选择作物、选择肥料、加载计数、提示更新计时器、当前悬停位置和虫害保护标志
 thread_local! {
     static FARM: RefCell<Farm> = RefCell::new(Farm::new(10, 10));
     static SHOP: RefCell<Shop> = RefCell::new(Shop::new());
-    static WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static CORN_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static SEED_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static SHOP_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static PREMIUM_WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static GOLDEN_WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static PREMIUM_CORN_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static GOLDEN_CORN_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static PREMIUM_CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static GOLDEN_CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static SELECTED_CROP: RefCell<CropType> = RefCell::new(CropType::Wheat);
-    static SELECTED_FERTILIZER: RefCell<String> = RefCell::new("basic_fertilizer".to_string());
-    static LOADED_COUNT: RefCell<u32> = RefCell::new(0);
-    static TOOLTIP_UPDATE_TIMER: RefCell<Option<i32>> = RefCell::new(None);
-    static CURRENT_HOVERED_POSITION: RefCell<Option<(usize, usize, i32, i32)>> = RefCell::new(None);
-    static BUG_PROTECTION_ENABLED: RefCell<bool> = RefCell::new(false);
-    static PREMIUM_SEED_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
-    static GOLD_SEED_IMAGE: RefCell<Option<HtmlImageElement>> = RefCell::new(None);
+    static WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static CORN_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static SEED_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static SHOP_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static PREMIUM_WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static GOLDEN_WHEAT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static PREMIUM_CORN_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static GOLDEN_CORN_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static PREMIUM_CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static GOLDEN_CARROT_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static SELECTED_CROP: RefCell<CropType> = const { RefCell::new(CropType::Wheat) };
+    static SELECTED_FERTILIZER: RefCell<String> = RefCell::new(default_fertilizer());
+    static LOADED_COUNT: RefCell<u32> = const { RefCell::new(0) };
+    static TOOLTIP_UPDATE_TIMER: RefCell<Option<i32>> = const { RefCell::new(None) };
+    static CURRENT_HOVERED_POSITION: RefCell<Option<(usize, usize, i32, i32)>> = const { RefCell::new(None) };
+    static BUG_PROTECTION_ENABLED: RefCell<bool> = const { RefCell::new(false) };
+    static PREMIUM_SEED_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
+    static GOLD_SEED_IMAGE: RefCell<Option<HtmlImageElement>> = const { RefCell::new(None) };
     
 
     // 定义任务列表
-    static TASKS: RefCell<Vec<Task>> = RefCell::new(vec![
-        Task {
-            id: 1,
-            description: "种植小麦10个".to_string(),
-            task_type: TaskType::PlantCrop { crop: "wheat".to_string(), count: 10 },
-            progress: 0,
-            target: 10,
-            reward: 30,
-            completed: false,
-            claimed: false,
-        },
-        Task {
-            id: 2,
-            description: "种植玉米5个".to_string(),
-            task_type: TaskType::PlantCrop { crop: "corn".to_string(), count: 5 },
-            progress: 0,
-            target: 5,
-            reward: 20,
-            completed: false,
-            claimed: false,
-        },
-        Task {
-            id: 3,
-            description: "种植胡萝卜3个".to_string(),
-            task_type: TaskType::PlantCrop { crop: "carrot".to_string(), count: 3 },
-            progress: 0,
-            target: 3,
-            reward: 15,
-            completed: false,
-            claimed: false,
-        },
-    ]);
+    static TASKS: RefCell<Vec<Task>> = RefCell::new(task::default_tasks());
+    // 定义任务链进度
+    static QUESTS: RefCell<QuestLog> = RefCell::new(QuestLog::new());
+    // 定义成就进度，与游戏存档分开保存
+    static ACHIEVEMENTS: RefCell<AchievementTracker> = RefCell::new(AchievementTracker::new());
+    // 定义经济统计数据
+    static STATS: RefCell<Statistics> = RefCell::new(Statistics::new());
+    // 定义可撤销的操作记录
+    static UNDO: RefCell<UndoHistory> = RefCell::new(UndoHistory::new());
+    // 定义操作日志，配合随机种子可以回放整局游戏
+    static ACTION_LOG: RefCell<ActionLog> = RefCell::new(ActionLog::new(rand::random()));
+    // 是否正在回放操作日志
+    static REPLAYING: RefCell<bool> = const { RefCell::new(false) };
+    // 定义自动化规则
+    static RULES: RefCell<RuleBook> = RefCell::new(RuleBook::new());
+    // 最近一次失败操作的原因
 }
 
 // 尝试播放音乐
@@ -133,139 +186,399 @@ pub fn try_play_music() {
 // 处理时间流逝
 #[wasm_bindgen]
 pub fn tick() {
-    BUG_PROTECTION_ENABLED.with(|flag| {
+    let ticks = FARM.with(|farm| farm.borrow().ticks);
+    let seed = ACTION_LOG.with(|log| log.borrow().seed);
+    rng::reseed(seed, ticks << 32);
+    let infested = BUG_PROTECTION_ENABLED.with(|flag| {
         FARM.with(|farm| {
             if *flag.borrow() {
                 farm.borrow_mut().tick_without_infestation();
+                Vec::new()
             } else {
-                farm.borrow_mut().tick();
+                farm.borrow_mut().tick()
             }
-        });
+        })
     });
+    for crop in infested {
+        record_stat(StatEvent::PestLoss(crop));
+    }
+    run_workers();
+    run_rules();
+    record_occupancy();
+    refresh_tasks();
 }
 
-// 应用虫害保护
+// 每个游戏日开始时支付帮工工资，然后已领工资的帮工开始工作
+fn run_workers() {
+    let new_day = FARM.with(|farm| farm.borrow().ticks.is_multiple_of(farm::TICKS_PER_DAY));
+    if new_day {
+        pay_wages();
+    }
+    let results = FARM.with(|farm| farm.borrow_mut().tick_workers());
+    for result in results {
+        match result {
+            WorkResult::Harvested(result) => record_harvest(result),
+            WorkResult::Planted(crop) => {
+                record_task_event(TaskEvent::Planted(crop));
+                record_stat(StatEvent::Planted(crop));
+            }
+            WorkResult::Sprayed => record_task_event(TaskEvent::PestCleared),
+        }
+    }
+}
+
+// 执行自动化规则，并记录规则带来的任务进度和统计
+fn run_rules() {
+    // 执行规则时农场已被借用，先判断规则中的种子是否已解锁
+    let unlocked = RULES.with(|rules| {
+        rules
+            .borrow()
+            .rules
+            .iter()
+            .filter_map(|entry| match entry.rule {
+                Rule::BuySeedsBelow { seed, .. } => Some(seed),
+                _ => None,
+            })
+            .filter(|seed| is_item_unlocked(seed.id()))
+            .collect::<Vec<_>>()
+    });
+    let effects = RULES.with(|rules| {
+        let mut rules = rules.borrow_mut();
+        if rules.rules.is_empty() {
+            return Vec::new();
+        }
+        FARM.with(|farm| {
+            SHOP.with(|shop| {
+                rules.run(&mut farm.borrow_mut(), &mut shop.borrow_mut(), |id| unlocked.iter().any(|seed| seed.id() == id))
+            })
+        })
+    });
+    for effect in effects {
+        match effect {
+            RuleEffect::Harvested(result) => record_harvest(result),
+            RuleEffect::Planted(crop) => {
+                record_task_event(TaskEvent::Planted(crop));
+                record_stat(StatEvent::Planted(crop));
+            }
+            RuleEffect::Sold { crop, revenue } => {
+                record_task_event(TaskEvent::Sold(crop));
+                record_task_event(TaskEvent::Earned(revenue));
+                record_stat(StatEvent::Sold { crop, revenue });
+            }
+            RuleEffect::SeedBought { crop, cost } => record_stat(StatEvent::SeedBought { crop, cost }),
+            // 规则每次时间流逝都会执行，失败只记入日志和规则的失败次数，不弹出提示
+            RuleEffect::Failed { rule_id, error } => {
+                if !crate::utils::is_muted() {
+                    web_sys::console::warn_1(&tf!("log.rule_failed", id = rule_id, reason = error.message()).into());
+                }
+            }
+        }
+    }
+}
+
+// 支付所有帮工当天的工资，金币不足的帮工当天停工；前一天没有干活的帮工沿用已付的工资
+fn pay_wages() {
+    let wages = FARM.with(|farm| farm.borrow().crew.wages_due());
+    let paid = wages
+        .iter()
+        .map(|wage| {
+            let Some(wage) = *wage else {
+                return true;
+            };
+            let ok = SHOP.with(|shop| shop.borrow_mut().pay_wage(wage)).is_ok();
+            if ok {
+                record_stat(StatEvent::Spent(wage));
+            }
+            ok
+        })
+        .collect::<Vec<_>>();
+    FARM.with(|farm| farm.borrow_mut().crew.start_day(&paid));
+    let unpaid = paid.iter().filter(|ok| !**ok).count();
+    if unpaid > 0 {
+        crate::utils::show_message(&tf!("message.workers_unpaid", count = unpaid));
+    }
+}
+
+// 当前的任务、经验和统计进度
+fn progress() -> undo::Progress {
+    undo::Progress {
+        tasks: TASKS.with(|tasks| tasks.borrow().clone()),
+        quests: QUESTS.with(|quests| quests.borrow().clone()),
+        xp: FARM.with(|farm| farm.borrow().xp),
+        level: FARM.with(|farm| farm.borrow().level),
+        statistics: STATS.with(|stats| stats.borrow().clone()),
+        achievements: ACHIEVEMENTS.with(|achievements| achievements.borrow().clone()),
+    }
+}
+
+// 在操作开始前记录状态，tile 为操作会修改的地块
+fn checkpoint(tile: Option<(usize, usize)>) -> Checkpoint {
+    let balance = get_balance();
+    let progress = progress();
+    FARM.with(|farm| {
+        let farm = farm.borrow();
+        let tile = tile.and_then(|(row, col)| farm.tile(row, col).ok().map(|t| (row, col, t.state)));
+        Checkpoint {
+            tick: farm.ticks,
+            tile,
+            inventory: farm.inventory.clone(),
+            balance,
+            progress,
+        }
+    })
+}
+
+// 操作成功后加入撤销记录
+fn record_action(kind: ActionKind, checkpoint: Checkpoint) {
+    let balance = get_balance();
+    let progress = progress();
+    let entry = FARM.with(|farm| checkpoint.finish(kind, &farm.borrow(), balance, &progress));
+    UNDO.with(|history| history.borrow_mut().push(entry));
+}
+
+// 记录经济统计
+fn record_stat(event: StatEvent) {
+    let day = FARM.with(|farm| farm.borrow().day());
+    STATS.with(|stats| stats.borrow_mut().record(day, event));
+}
+
+// 记录当前占用地块的作物，用于计算每块地的收益效率
+fn record_occupancy() {
+    FARM.with(|farm| {
+        let farm = farm.borrow();
+        let crops = farm.grid.iter().flatten().filter_map(|tile| tile.state.crop()).collect::<Vec<_>>();
+        STATS.with(|stats| stats.borrow_mut().record_occupancy(farm.day(), &crops));
+    });
+}
+
+// 记录玩家行为并推进任务进度
+fn record_task_event(event: TaskEvent) {
+    TASKS.with(|tasks| task::record_event(&mut tasks.borrow_mut(), &event));
+    QUESTS.with(|quests| quests.borrow_mut().record_event(&event));
+    // 成就跨存档累计，回放时不重复计入
+    if !is_replaying() {
+        ACHIEVEMENTS.with(|achievements| achievements.borrow_mut().record_event(&event));
+    }
+    gain_xp(progression::xp_for_event(&event));
+}
+
+fn is_replaying() -> bool {
+    REPLAYING.with(|flag| *flag.borrow())
+}
+
+// 增加经验，升级时提示玩家
+fn gain_xp(amount: u32) {
+    let (gained, level) = FARM.with(|farm| {
+        let mut farm = farm.borrow_mut();
+        (farm.add_xp(amount), farm.level)
+    });
+    if gained > 0 {
+        crate::utils::show_message(&tf!("message.level_up", level = level));
+    }
+}
+
+// 判断物品是否已通过等级或任务链解锁
+fn is_item_unlocked(item: &str) -> bool {
+    let required = progression::required_level(item);
+    let quest_locked = quest::is_quest_locked(item);
+    if required.is_none() && !quest_locked {
+        return true;
+    }
+    let level = FARM.with(|farm| farm.borrow().level);
+    required.is_some_and(|r| level >= r)
+        || (quest_locked && QUESTS.with(|quests| quests.borrow().is_unlocked(item)))
+}
+
+// 检查背包是否还有空间
+fn check_backpack_room() -> Result<(), GameError> {
+    if FARM.with(|farm| farm.borrow().inventory.free(Pool::Backpack)) > 0 {
+        return Ok(());
+    }
+    Err(GameError::BackpackFull { needed: 1 })
+}
+
+// 检查物品是否已解锁
+fn check_unlocked(item: &str) -> Result<(), GameError> {
+    if is_item_unlocked(item) {
+        return Ok(());
+    }
+    Err(GameError::ItemLocked {
+        item: item.to_string(),
+        level: progression::required_level(item),
+    })
+}
+
+// 刷新持有类任务的进度，并替换已过期的任务
+fn refresh_tasks() {
+    let balance = SHOP.with(|shop| shop.borrow().get_balance());
+    let expired = FARM.with(|farm| {
+        let farm = farm.borrow();
+        QUESTS.with(|quests| quests.borrow_mut().refresh_state(&farm.inventory, balance));
+        TASKS.with(|tasks| {
+            let mut tasks = tasks.borrow_mut();
+            task::refresh_state(&mut tasks, &farm.inventory, balance);
+            task::replace_expired(&mut tasks, farm.day())
+        })
+    });
+    if expired > 0 {
+        crate::utils::show_message(t("message.tasks_expired"));
+    }
+}
+
+// 获取当前游戏天数
 #[wasm_bindgen]
-pub fn apply_bug_protection() {
+pub fn get_game_day() -> u32 {
+    FARM.with(|farm| farm.borrow().day())
+}
+
+// 应用虫害保护
+fn bug_protection_action() {
     BUG_PROTECTION_ENABLED.with(|flag| *flag.borrow_mut() = true);
 
     // 清除现有害虫
-    FARM.with(|farm| {
+    let cleared = FARM.with(|farm| {
         let mut farm = farm.borrow_mut();
+        let mut cleared = 0;
         for row in farm.grid.iter_mut() {
             for tile in row.iter_mut() {
-                if let TileState::Infested { crop } = tile.state {
+                if let TileState::Infested { crop, harvests } = tile.state {
                     tile.state = TileState::Planted {
                         crop,
                         timer: 0,
                         fertilizer: FertilizerType::None,
+                        harvests,
                     };
+                    cleared += 1;
                 }
             }
         }
+        cleared
     });
+    for _ in 0..cleared {
+        record_task_event(TaskEvent::PestCleared);
+    }
 
     play_sound("click.wav");
-    crate::utils::show_message("🕸️ 捕虫网部署完成！");
-    let _ = save_game();
+    crate::utils::show_message(t("message.net_deployed"));
+    autosave();
 }
 
-// 获取作物信息
+// 获取作物信息，坐标超出网格范围时返回 { code, params }
 #[wasm_bindgen]
-pub fn get_crop_info(row: usize, col: usize) -> String {
-    FARM.with(|farm| farm.borrow().get_crop_info(row, col))
+pub fn get_crop_info(row: usize, col: usize) -> Result<String, JsValue> {
+    FARM.with(|farm| farm.borrow().get_crop_info(row, col)).map_err(|err| serde_wasm_bindgen::to_value(&err).unwrap())
+}
+
+// 鼠标所在地块的提示文字，坐标不在农场内时返回 None
+fn tooltip_text(row: usize, col: usize) -> Option<String> {
+    let crop_info = FARM.with(|farm| farm.borrow().get_crop_info(row, col)).ok()?;
+    // get_crop_info 已经包含完整信息，没有信息时只显示位置
+    Some(if crop_info.is_empty() { tf!("tooltip.position", row = row, col = col) } else { crop_info })
 }
 
-// 喷洒地块
+// 获取地块的结构化信息（状态、作物、进度、肥料、虫害和区域效果），坐标超出网格范围时返回 { code, params }
 #[wasm_bindgen]
-pub fn spray_tile(row: usize, col: usize) {
-    FARM.with(|farm| {
-        let mut farm = farm.borrow_mut();
-        if row < farm.grid.len() && col < farm.grid[0].len() {
-            if let TileState::Infested { crop, .. } = farm.grid[row][col].state {
-                // 只有遭到虫害时才清除害虫
-                farm.grid[row][col].state = TileState::Planted {
-                    crop,
-                    timer: 0,
-                    fertilizer: FertilizerType::None,
-                };
-                crate::utils::play_sound("click.wav");
-                crate::utils::show_message("🐛 害虫已清除！");
-            } else {
-                // 没有害虫的情况
-                crate::utils::show_message("🚫 这里没有害虫需要清除");
-            }
-        }
-    });
-    
-    let _ = save_game();
+pub fn get_tile_info(row: usize, col: usize) -> Result<JsValue, JsValue> {
+    tile_info(row, col)
+        .map(|info| serde_wasm_bindgen::to_value(&info).unwrap())
+        .map_err(|err| serde_wasm_bindgen::to_value(&err).unwrap())
 }
 
+fn tile_info(row: usize, col: usize) -> Result<TileInfo, GameError> {
+    let bug_net = BUG_PROTECTION_ENABLED.with(|flag| *flag.borrow());
+    FARM.with(|farm| farm.borrow().tile_info(row, col, bug_net))
+}
+
+// 喷洒地块，只有遭到虫害时才清除害虫
+fn spray_tile_action(row: usize, col: usize) -> Result<(), GameError> {
+    FARM.with(|farm| farm.borrow_mut().spray(row, col))?;
+    crate::utils::play_sound("click.wav");
+    crate::utils::show_message(t("message.pests_cleared"));
+    record_task_event(TaskEvent::PestCleared);
+    autosave();
+    Ok(())
+}
 
 // 种植作物
-#[wasm_bindgen]
-pub fn plant(row: usize, col: usize, crop: String) {
-    let crop_type = match crop.as_str() {
-        "wheat" => CropType::Wheat,
-        "premium_wheat" => CropType::PremiumWheat,
-        "golden_wheat" => CropType::GoldenWheat,
-        "corn" => CropType::Corn,
-        "premium_corn" => CropType::PremiumCorn,
-        "golden_corn" => CropType::GoldenCorn,
-        "carrot" => CropType::Carrot,
-        "premium_carrot" => CropType::PremiumCarrot,
-        "golden_carrot" => CropType::GoldenCarrot,
-        _ => CropType::Wheat,
-    };
+fn plant_action(row: usize, col: usize, crop: String) -> Result<(), GameError> {
+    let crop_type = item::parse_crop(&crop)?;
     SELECTED_CROP.with(|selected| *selected.borrow_mut() = crop_type);
-    let success = FARM.with(|farm| farm.borrow_mut().plant(row, col, crop_type, crop.clone()));
-    if success {
-        play_sound("plant_seed.mp3");
-        TASKS.with(|tasks| {
-            let mut tasks = tasks.borrow_mut();
-            for task in tasks.iter_mut() {
-                let TaskType::PlantCrop { crop: ref task_crop, count: _ } = &task.task_type;
-                // 新增：判断主类是否一致
-                let is_same_main_type = match (task_crop.as_str(), crop_type) {
-                    ("wheat", CropType::Wheat) => true,
-                    ("premium_wheat", CropType::PremiumWheat) => true,
-                    ("golden_wheat", CropType::GoldenWheat) => true,
-                    ("corn", CropType::Corn) => true,
-                    ("premium_corn", CropType::PremiumCorn) => true,
-                    ("golden_corn", CropType::GoldenCorn) => true,
-                    ("carrot", CropType::Carrot) => true,
-                    ("premium_carrot", CropType::PremiumCarrot) => true,
-                    ("golden_carrot", CropType::GoldenCarrot) => true,
-                    _ => false,
-                };
-                if !task.completed && is_same_main_type {
-                    task.progress += 1;
-                    if task.progress >= task.target {
-                        task.completed = true;
-                    }
-                }
-            }
-        });
-        let _ = save_game();
-    } else {
-        web_sys::console::log_1(&"种植失败：没有足够的种子或地块不为空".into());
-    }
+    let checkpoint = checkpoint(Some((row, col)));
+    FARM.with(|farm| farm.borrow_mut().plant(row, col, crop_type))?;
+    play_sound("plant_seed.mp3");
+    record_task_event(TaskEvent::Planted(crop_type));
+    record_stat(StatEvent::Planted(crop_type));
+    record_action(ActionKind::Plant, checkpoint);
+    autosave();
+    Ok(())
 }
 
 // 收获作物
+fn harvest_action(row: usize, col: usize) -> Result<(), GameError> {
+    let checkpoint = checkpoint(Some((row, col)));
+    let result = FARM.with(|farm| farm.borrow_mut().harvest(row, col))?;
+    play_sound("sell_crop.wav");
+    record_harvest(result);
+    record_action(ActionKind::Harvest, checkpoint);
+    autosave();
+    Ok(())
+}
+
+// 记录一次收获，杂交成功时提示玩家
+fn record_harvest(result: HarvestResult) {
+    record_task_event(TaskEvent::Harvested(result.crop));
+    record_stat(StatEvent::Harvested(result.crop));
+    if let Some(outcome) = result.breeding {
+        let is_new = FARM.with(|farm| farm.borrow_mut().codex.discover(outcome.seed()));
+        let mut msg = match outcome {
+            BreedingOutcome::Upgrade(seed) => tf!("message.bred_upgrade", seed = seed.display_name()),
+            BreedingOutcome::Mutation(seed) => tf!("message.bred_mutation", seed = seed.display_name()),
+        };
+        if is_new {
+            msg.push_str(t("message.codex_discovery"));
+        }
+        crate::utils::show_message(&msg);
+    }
+}
+
+// 获取品种图鉴
+#[wasm_bindgen]
+pub fn get_codex() -> JsValue {
+    FARM.with(|farm| {
+        serde_wasm_bindgen::to_value(&farm.borrow().codex).unwrap()
+    })
+}
+
+// 获取所有地块的状态以及金币和库存数量，渲染和界面刷新每帧只需调用一次
 #[wasm_bindgen]
-pub fn harvest(row: usize, col: usize) {
-    FARM.with(|farm| farm.borrow_mut().harvest(row, col));
-    play_sound("sell_crop.wav"); 
-    let _ = save_game();
+pub fn get_farm_snapshot() -> JsValue {
+    serde_wasm_bindgen::to_value(&farm_snapshot()).unwrap()
+}
+
+fn farm_snapshot() -> FarmSnapshot {
+    let balance = SHOP.with(|shop| shop.borrow().get_balance());
+    FARM.with(|farm| farm.borrow().snapshot(balance))
 }
 
-// 获取地块状态
+// 获取地块状态，坐标超出网格范围时返回 { code, params }
 #[wasm_bindgen]
-pub fn get_state(row: usize, col: usize) -> String {
+pub fn get_state(row: usize, col: usize) -> Result<String, JsValue> {
+    tile_state(row, col).map_err(|err| serde_wasm_bindgen::to_value(&err).unwrap())
+}
+
+// 地块状态的编号，例如 empty、planted_wheat、machine_mill
+fn tile_state(row: usize, col: usize) -> Result<String, GameError> {
     FARM.with(|farm| {
-        let tile = &farm.borrow().grid[row][col];
-        match tile.state {
+        let farm = farm.borrow();
+        let tile = farm.tile(row, col)?;
+        match &tile.content {
+            TileContent::Machine(machine) => return Ok(format!("machine_{}", machine.kind.id())),
+            TileContent::Structure { kind, .. } => return Ok(format!("structure_{}", kind.id())),
+            TileContent::Pen(pen) => return Ok(format!("pen_{}", pen.kind.id())),
+            TileContent::Locked => return Ok("locked".into()),
+            TileContent::Soil => {}
+        }
+        let state = match tile.state {
             TileState::Empty => "empty".into(),
             TileState::Planted { crop, .. } => match crop {
                 CropType::Wheat => "planted_wheat".into(),
@@ -277,8 +590,10 @@ pub fn get_state(row: usize, col: usize) -> String {
                 CropType::Carrot => "planted_carrot".into(),
                 CropType::PremiumCarrot => "planted_premium_carrot".into(),
                 CropType::GoldenCarrot => "planted_golden_carrot".into(),
+                CropType::Strawberry => "planted_strawberry".into(),
+                CropType::Apple => "planted_apple".into(),
             },
-            TileState::Mature { crop } => match crop {
+            TileState::Mature { crop, .. } => match crop {
                 CropType::Wheat => "mature_wheat".into(),
                 CropType::PremiumWheat => "mature_premium_wheat".into(),
                 CropType::GoldenWheat => "mature_golden_wheat".into(),
@@ -288,8 +603,10 @@ pub fn get_state(row: usize, col: usize) -> String {
                 CropType::Carrot => "mature_carrot".into(),
                 CropType::PremiumCarrot => "mature_premium_carrot".into(),
                 CropType::GoldenCarrot => "mature_golden_carrot".into(),
+                CropType::Strawberry => "mature_strawberry".into(),
+                CropType::Apple => "mature_apple".into(),
             },
-            TileState::Infested { crop } => match crop {
+            TileState::Infested { crop, .. } => match crop {
                 CropType::Wheat => "infested_wheat".into(),
                 CropType::PremiumWheat => "infested_premium_wheat".into(),
                 CropType::GoldenWheat => "infested_golden_wheat".into(),
@@ -299,51 +616,190 @@ pub fn get_state(row: usize, col: usize) -> String {
                 CropType::Carrot => "infested_carrot".into(),
                 CropType::PremiumCarrot => "infested_premium_carrot".into(),
                 CropType::GoldenCarrot => "infested_golden_carrot".into(),
+                CropType::Strawberry => "infested_strawberry".into(),
+                CropType::Apple => "infested_apple".into(),
             },
-        }
+        };
+        Ok(state)
     })
 }
 
-// 施肥
-#[wasm_bindgen]
-pub fn fertilize(row: usize, col: usize) -> bool {
-    let fertilizer_type = SELECTED_FERTILIZER.with(|f| f.borrow().clone());
-    let result = FARM.with(|farm| {
-        farm.borrow_mut().fertilize(row, col, &fertilizer_type)
-    });
-    if result {
-        let _ = save_game();
+// 使用指定的肥料施肥，未指定时使用当前选择的肥料
+fn fertilize_action(row: usize, col: usize, fertilizer: Option<String>) -> Result<(), GameError> {
+    let fertilizer = selected_fertilizer(fertilizer)?;
+    let checkpoint = checkpoint(Some((row, col)));
+    let crop = FARM.with(|farm| {
+        let mut farm = farm.borrow_mut();
+        let crop = farm.tile(row, col).ok().and_then(|t| t.state.crop());
+        farm.fertilize(row, col, fertilizer).map(|_| crop)
+    })?;
+    record_task_event(TaskEvent::Fertilized);
+    if let Some(crop) = crop {
+        let cost = SHOP.with(|shop| shop.borrow().get_fertilizer_price(fertilizer).unwrap_or(0));
+        record_stat(StatEvent::FertilizerUsed { crop, cost });
     }
-    result
+    record_action(ActionKind::Fertilize, checkpoint);
+    autosave();
+    Ok(())
+}
+
+// 批量操作不进入撤销记录，改变了农场时清空之前的记录，避免撤销时回退批量操作带来的进度
+// action 为操作名称的消息键
+fn finish_area_action(action: &str, summary: &AreaSummary) {
+    let skipped = summary
+        .skipped
+        .iter()
+        .map(|s| tf!("area.skip_count", reason = s.message, count = s.count))
+        .collect::<Vec<_>>();
+    let mut msg = tf!("area.summary", action = t(action), count = summary.succeeded);
+    if !skipped.is_empty() {
+        msg.push_str(&tf!("area.skipped", skipped = i18n::join(&skipped)));
+    }
+    crate::utils::show_message(&msg);
+    if summary.succeeded > 0 {
+        // 区域操作无法撤销，农场改变后之前的记录也不再对应当前状态
+        UNDO.with(|history| history.borrow_mut().clear());
+        autosave();
+    }
+}
+
+// 收获所有成熟的作物
+fn harvest_all_action() -> AreaSummary {
+    let (results, summary) = FARM.with(|farm| farm.borrow_mut().harvest_all());
+    for result in results {
+        record_harvest(result);
+    }
+    if summary.succeeded > 0 {
+        play_sound("sell_crop.wav");
+    }
+    finish_area_action("area.harvest", &summary);
+    summary
+}
+
+// 在矩形区域内种植作物
+fn plant_rect_action(r0: usize, c0: usize, r1: usize, c1: usize, crop: String) -> Result<AreaSummary, GameError> {
+    let crop_type = item::parse_crop(&crop)?;
+    SELECTED_CROP.with(|selected| *selected.borrow_mut() = crop_type);
+    let summary = FARM.with(|farm| farm.borrow_mut().plant_rect(r0, c0, r1, c1, crop_type));
+    for _ in 0..summary.succeeded {
+        record_task_event(TaskEvent::Planted(crop_type));
+        record_stat(StatEvent::Planted(crop_type));
+    }
+    if summary.succeeded > 0 {
+        play_sound("plant_seed.mp3");
+    }
+    finish_area_action("area.plant", &summary);
+    Ok(summary)
+}
+
+// 在矩形区域内施肥，未指定肥料时使用当前选择的肥料
+fn fertilize_rect_action(r0: usize, c0: usize, r1: usize, c1: usize, fertilizer: Option<String>) -> Result<AreaSummary, GameError> {
+    let fertilizer = selected_fertilizer(fertilizer)?;
+    let (crops, summary) = FARM.with(|farm| farm.borrow_mut().fertilize_rect(r0, c0, r1, c1, fertilizer));
+    let cost = SHOP.with(|shop| shop.borrow().get_fertilizer_price(fertilizer).unwrap_or(0));
+    for crop in crops {
+        record_task_event(TaskEvent::Fertilized);
+        record_stat(StatEvent::FertilizerUsed { crop, cost });
+    }
+    finish_area_action("area.fertilize", &summary);
+    Ok(summary)
+}
+
+// 清除所有地块上的害虫
+fn spray_all_infested_action() -> AreaSummary {
+    let summary = FARM.with(|farm| farm.borrow_mut().spray_all_infested());
+    for _ in 0..summary.succeeded {
+        record_task_event(TaskEvent::PestCleared);
+    }
+    if summary.succeeded > 0 {
+        play_sound("click.wav");
+    }
+    finish_area_action("area.spray", &summary);
+    summary
+}
+
+// 雇佣帮工，雇佣时支付当天的工资
+fn hire_worker_action(role: String, crop: Option<String>, area: Area) -> Result<(), GameError> {
+    if let Some(crop) = crop.as_deref().filter(|_| role == "planter") {
+        item::parse_crop(crop)?;
+    }
+    let role = WorkerRole::from_id(&role, crop.as_deref()).ok_or(GameError::UnknownWorkerRole { role })?;
+    if FARM.with(|farm| farm.borrow().crew.is_full()) {
+        return Err(GameError::WorkerLimit { max: worker::MAX_WORKERS });
+    }
+    let wage = role.daily_wage();
+    SHOP.with(|shop| shop.borrow_mut().pay_wage(wage))?;
+    record_stat(StatEvent::Spent(wage));
+    FARM.with(|farm| farm.borrow_mut().crew.hire(role, area));
+    play_sound("sell_crop.wav");
+    crate::utils::show_message(&tf!("message.worker_hired", role = role.display_name(), wage = wage));
+    autosave();
+    Ok(())
+}
+
+// 解雇帮工，当天的工资不退还
+fn fire_worker_action(worker_id: u32) -> Result<(), GameError> {
+    if !FARM.with(|farm| farm.borrow_mut().crew.fire(worker_id)) {
+        return Err(GameError::WorkerNotFound { worker_id });
+    }
+    autosave();
+    Ok(())
+}
+
+// 添加自动化规则
+fn add_rule_action(rule: Rule) -> Result<(), GameError> {
+    RULES
+        .with(|rules| rules.borrow_mut().add(rule))
+        .ok_or(GameError::RuleLimit { max: rules::MAX_RULES })?;
+    autosave();
+    Ok(())
+}
+
+// 删除自动化规则
+fn remove_rule_action(rule_id: u32) -> Result<(), GameError> {
+    if !RULES.with(|rules| rules.borrow_mut().remove(rule_id)) {
+        return Err(GameError::RuleNotFound { rule_id });
+    }
+    autosave();
+    Ok(())
+}
+
+// 启用或停用自动化规则
+fn set_rule_enabled_action(rule_id: u32, enabled: bool) -> Result<(), GameError> {
+    if !RULES.with(|rules| rules.borrow_mut().set_enabled(rule_id, enabled)) {
+        return Err(GameError::RuleNotFound { rule_id });
+    }
+    autosave();
+    Ok(())
+}
+
+// 操作指定了肥料时同时更新当前选择，与批量种植更新选择的作物一致
+fn selected_fertilizer(fertilizer: Option<String>) -> Result<FertilizerType, GameError> {
+    if let Some(fertilizer) = fertilizer {
+        select_fertilizer_action(fertilizer)?;
+    }
+    SELECTED_FERTILIZER.with(|f| item::parse_fertilizer(&f.borrow()))
 }
 
 // 选择肥料
-#[wasm_bindgen]
-pub fn select_fertilizer(fertilizer_type: String) {
+fn select_fertilizer_action(fertilizer_type: String) -> Result<(), GameError> {
+    item::parse_fertilizer(&fertilizer_type)?;
     SELECTED_FERTILIZER.with(|f| *f.borrow_mut() = fertilizer_type);
+    Ok(())
 }
 
 // 购买肥料
-#[wasm_bindgen]
-pub fn buy_fertilizer(fertilizer_type: String) -> bool {
-    let result = SHOP.with(|shop| {
-        let mut shop = shop.borrow_mut();
-        if shop.buy_fertilizer(&fertilizer_type) {
-            FARM.with(|farm| {
-                farm.borrow_mut().inventory.add_fertilizer(&fertilizer_type);
-            });
-            true
-        } else {
-            false
-        }
-    });
-    if result {
-        play_sound("sell_crop.wav"); 
-        let _ = save_game();
-    }else{
-        play_sound("buy_fail.wav"); 
-    }
-    result
+fn buy_fertilizer_action(fertilizer_type: String) -> Result<(), GameError> {
+    let fertilizer = item::parse_fertilizer(&fertilizer_type)?;
+    check_unlocked(&fertilizer_type)?;
+    check_backpack_room()?;
+    let checkpoint = checkpoint(None);
+    SHOP.with(|shop| shop.borrow_mut().buy_fertilizer(fertilizer))?;
+    FARM.with(|farm| farm.borrow_mut().inventory.add_fertilizer(fertilizer));
+    record_action(ActionKind::Buy, checkpoint);
+    play_sound("sell_crop.wav");
+    autosave();
+    Ok(())
 }
 
 // 获取完整库存
@@ -371,81 +827,160 @@ pub fn get_balance() -> u32 {
 }
 
 // 购买种子
-#[wasm_bindgen]
-pub fn buy_seed(seed_type: String) -> bool {
-    let result = SHOP.with(|shop| {
-        let mut shop = shop.borrow_mut();
-        if shop.buy_seed(&seed_type) {
-            FARM.with(|farm| {
-                farm.borrow_mut().inventory.add_seed(&seed_type);
-            });
-            true
-        } else {
-            false
-        }
-    });
-    if result {
-        play_sound("sell_crop.wav"); 
-        let _ = save_game();
-    }else{
-        play_sound("buy_fail.wav");
-    }
-    result
+fn buy_seed_action(seed_type: String) -> Result<(), GameError> {
+    let seed = item::parse_crop(&seed_type)?;
+    check_unlocked(&seed_type)?;
+    check_backpack_room()?;
+    let checkpoint = checkpoint(None);
+    let balance_before = get_balance();
+    SHOP.with(|shop| shop.borrow_mut().buy_seed(seed))?;
+    FARM.with(|farm| farm.borrow_mut().inventory.add_seed(seed));
+    record_action(ActionKind::Buy, checkpoint);
+    let cost = balance_before - get_balance();
+    record_stat(StatEvent::SeedBought { crop: seed, cost });
+    play_sound("sell_crop.wav");
+    autosave();
+    Ok(())
 }
 
-// 出售作物
-#[wasm_bindgen]
-pub fn sell_crop(crop_type: String) {
-    SHOP.with(|shop| {
-        let mut shop = shop.borrow_mut();
-        shop.sell_crop(&crop_type);
-    });
-    let _ = save_game();
-}
 
 // 保存游戏状态
 #[wasm_bindgen]
 pub fn save_game() -> Result<(), JsValue> {
-    let game_state = FARM.with(|farm| {
+    // 回放过程中的中间状态不保存，回放结束后统一保存
+    if is_replaying() {
+        return Ok(());
+    }
+    let game_state = game_state();
+    let storage = window().unwrap().local_storage()?.unwrap();
+    let json = serde_json::to_string(&game_state).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    storage.set_item("farm_game_state", &json)?;
+    let json = ACTION_LOG.with(|log| serde_json::to_string(&*log.borrow()))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    storage.set_item("farm_game_action_log", &json)?;
+    save_achievements()
+}
+
+// 操作后自动保存，失败时（例如存储空间已满）提示玩家，而不是悄悄丢失进度
+fn autosave() {
+    if let Err(err) = save_game() {
+        web_sys::console::error_1(&err);
+        crate::utils::show_message(t("message.save_failed"));
+    }
+}
+
+// 收集当前游戏状态用于保存
+fn game_state() -> GameState {
+    FARM.with(|farm| {
         let farm = farm.borrow();
         let grid = farm.grid.iter().map(|row| {
             row.iter().map(|tile| tile.state).collect::<Vec<_>>()
         }).collect::<Vec<_>>();
         
-        let (seeds, crops, fertilizers) = farm.get_full_inventory();
         let balance = SHOP.with(|shop| shop.borrow().get_balance());
         let tasks = TASKS.with(|tasks| tasks.borrow().clone()); // 新增
+        let codex = farm.codex.clone();
+        let contents = farm.grid.iter().map(|row| {
+            row.iter().map(|tile| tile.content.clone()).collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+        let seed_maker = farm.seed_maker.clone();
+        let quests = QUESTS.with(|quests| quests.borrow().clone());
+        let statistics = STATS.with(|stats| stats.borrow().clone());
+        let bug_protection = BUG_PROTECTION_ENABLED.with(|flag| *flag.borrow());
+        let crew = farm.crew.clone();
+        let rules = RULES.with(|rules| rules.borrow().clone());
+        let selected_fertilizer = SELECTED_FERTILIZER.with(|f| f.borrow().clone());
         
         GameState {
             farm_grid: grid,
-            inventory_seeds: seeds,
-            inventory_crops: crops,
-            inventory_fertilizers: fertilizers,
+            inventory_seeds: farm.inventory.seeds.clone(),
+            inventory_crops: Default::default(),
+            inventory_fertilizers: farm.inventory.fertilizers.clone(),
+            inventory_goods: farm.inventory.goods.clone(),
+            inventory_buildings: farm.inventory.buildings.clone(),
+            inventory_items: farm.inventory.items.clone(),
+            inventory_crop_batches: farm.inventory.crop_batches.clone(),
+            farm_contents: contents,
+            ticks: farm.ticks,
+            xp: farm.xp,
+            level: farm.level,
             balance,
             tasks, // 新增
+            codex,
+            seed_maker,
+            quests,
+            statistics,
+            action_log: Default::default(),
+            bug_protection,
+            crew,
+            rules,
+            selected_fertilizer,
         }
-    });
+    })
+}
 
+// 保存成就进度，使用单独的存储键，清除存档时不受影响
+fn save_achievements() -> Result<(), JsValue> {
     let storage = window().unwrap().local_storage()?.unwrap();
-    let json = serde_json::to_string(&game_state).map_err(|e| JsValue::from_str(&e.to_string()))?;
-    storage.set_item("farm_game_state", &json)?;
+    let json = ACHIEVEMENTS.with(|achievements| serde_json::to_string(&*achievements.borrow()))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    storage.set_item("farm_game_achievements", &json)?;
     Ok(())
 }
 
+// 加载成就进度，并记录今天的游玩
+fn load_achievements() -> Result<(), JsValue> {
+    let storage = window().unwrap().local_storage()?.unwrap();
+    if let Some(json) = storage.get_item("farm_game_achievements")? {
+        let tracker: AchievementTracker = serde_json::from_str(&json)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        ACHIEVEMENTS.with(|achievements| *achievements.borrow_mut() = tracker);
+    }
+    let today = (js_sys::Date::now() / 86_400_000.0) as u64;
+    ACHIEVEMENTS.with(|achievements| achievements.borrow_mut().record_play_day(today));
+    save_achievements()
+}
+
+// 存档无法读取时提示玩家，并把原存档备份到另一个键，避免之后保存新游戏时被覆盖
+fn report_load_failure(key: &str, err: &JsValue, message: &str) {
+    web_sys::console::error_1(err);
+    if let Some(storage) = window().and_then(|win| win.local_storage().ok().flatten()) {
+        if let Ok(Some(json)) = storage.get_item(key) {
+            let _ = storage.set_item(&format!("{}_backup", key), &json);
+        }
+    }
+    crate::utils::show_message(t(message));
+}
+
 // 清除地块
-#[wasm_bindgen]
-pub fn clear_tile(row: usize, col: usize) {
-    FARM.with(|farm| {
+fn clear_tile_action(row: usize, col: usize) -> Result<(), GameError> {
+    let checkpoint = checkpoint(Some((row, col)));
+    let crop_cleared = FARM.with(|farm| {
         let mut farm = farm.borrow_mut();
-        if row < farm.grid.len() && col < farm.grid[0].len() {
-            if !farm.grid[row][col].can_plant() {
-                farm.grid[row][col].state = TileState::Empty;
-                crate::utils::show_message("🌿 作物已被清除！");
-                crate::utils::play_sound("audio/plant_seed.wav"); // 有这个音效才加
-            }
+        let building_removed = if farm.tile(row, col)?.pen().is_some() {
+            farm.remove_pen(row, col).map(|_| true)?
+        } else {
+            farm.remove_machine(row, col) || farm.remove_structure(row, col)?
+        };
+        if building_removed {
+            crate::utils::show_message(t("message.building_removed"));
+            return Ok(false);
         }
-    });
-    let _ = save_game();
+        let tile = farm.tile_mut(row, col)?;
+        if tile.state.crop().is_none() {
+            return Err(GameError::NothingToClear);
+        }
+        tile.state = TileState::Empty;
+        crate::utils::show_message(t("message.crop_cleared"));
+        crate::utils::play_sound("audio/plant_seed.wav"); // 有这个音效才加
+        Ok(true)
+    })?;
+    // 建筑可以直接放回，只有清除作物需要撤销
+    if crop_cleared {
+        record_action(ActionKind::Clear, checkpoint);
+    }
+    autosave();
+    Ok(())
 }
 
 // 加载游戏状态
@@ -453,52 +988,122 @@ pub fn clear_tile(row: usize, col: usize) {
 pub fn load_game() -> Result<(), JsValue> {
     let storage = window().unwrap().local_storage()?.unwrap();
     if let Some(json) = storage.get_item("farm_game_state")? {
-        let game_state: GameState = serde_json::from_str(&json)
+        let mut game_state: GameState = serde_json::from_str(&json)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
-        FARM.with(|farm| {
-            let mut farm = farm.borrow_mut();
-            for (row_idx, row) in game_state.farm_grid.iter().enumerate() {
-                for (col_idx, &state) in row.iter().enumerate() {
-                    farm.grid[row_idx][col_idx].state = state;
-                }
+        let legacy_log = std::mem::take(&mut game_state.action_log);
+        apply_game_state(game_state);
+        // 旧存档的操作日志保存在游戏状态中
+        let mut action_log = match load_action_log() {
+            Ok(Some(log)) => log,
+            Ok(None) => legacy_log,
+            Err(err) => {
+                report_load_failure("farm_game_action_log", &err, "message.action_log_reset");
+                ActionLog { replayable: false, ..ActionLog::default() }
             }
-            farm.inventory.seeds = game_state.inventory_seeds;
-            farm.inventory.crops = game_state.inventory_crops;
-            farm.inventory.fertilizers = game_state.inventory_fertilizers;
-        });
-        
-        SHOP.with(|shop| {
-            let mut shop = shop.borrow_mut();
-            shop.balance = game_state.balance;
-        });
-        TASKS.with(|tasks| {
-            *tasks.borrow_mut() = game_state.tasks.clone(); // 新增
-        });
+        };
+        // 旧存档没有操作日志，使用新的随机种子继续游戏，但日志无法回放
+        if !action_log.replayable && action_log.entries.is_empty() {
+            action_log.seed = rand::random();
+        }
+        ACTION_LOG.with(|log| *log.borrow_mut() = action_log);
     }
     Ok(())
 }
 
-// 清除保存
-#[wasm_bindgen]
-pub fn clear_save() -> Result<(), JsValue> {
+fn load_action_log() -> Result<Option<ActionLog>, JsValue> {
     let storage = window().unwrap().local_storage()?.unwrap();
-    storage.remove_item("farm_game_state")?;
-    
+    match storage.get_item("farm_game_action_log")? {
+        Some(json) => serde_json::from_str(&json).map(Some).map_err(|e| JsValue::from_str(&e.to_string())),
+        None => Ok(None),
+    }
+}
+
+// 用存档替换当前游戏状态，操作日志由调用方处理
+fn apply_game_state(game_state: GameState) {
     FARM.with(|farm| {
         let mut farm = farm.borrow_mut();
-        farm.grid = vec![vec![Tile { state: TileState::Empty }; 10]; 10];
-        farm.inventory = Inventory::new();
-    });
+        for (row_idx, row) in game_state.farm_grid.iter().enumerate() {
+            for (col_idx, &state) in row.iter().enumerate() {
+                if let Ok(tile) = farm.tile_mut(row_idx, col_idx) {
+                    tile.state = state;
+                }
+            }
+        }
+        farm.inventory.seeds = game_state.inventory_seeds;
+        farm.inventory.crop_batches = game_state.inventory_crop_batches;
+        // 旧存档没有地块内容，土地视为已全部开垦
+        if game_state.farm_contents.is_empty() {
+            farm.unlock_plots(u32::MAX);
+        }
+        for (row_idx, row) in game_state.farm_contents.into_iter().enumerate() {
+            for (col_idx, content) in row.into_iter().enumerate() {
+                if let Ok(tile) = farm.tile_mut(row_idx, col_idx) {
+                    tile.content = content;
+                }
+            }
+        }
+        farm.ticks = game_state.ticks;
+        // 旧存档没有收获批次，现有作物视为刚收获
+        let ticks = farm.ticks;
+        farm.inventory.add_legacy_crops(game_state.inventory_crops, ticks);
+        farm.level = game_state.level;
+        farm.xp = game_state.xp.max(progression::xp_for_level(game_state.level));
+        farm.inventory.fertilizers = game_state.inventory_fertilizers;
+        farm.inventory.goods = game_state.inventory_goods;
+        farm.inventory.buildings = game_state.inventory_buildings;
+        farm.inventory.items = game_state.inventory_items;
+        farm.codex = game_state.codex;
+        farm.seed_maker = game_state.seed_maker;
+        farm.crew = game_state.crew;
+        farm.refresh_capacity();
+    });
     
     SHOP.with(|shop| {
         let mut shop = shop.borrow_mut();
-        shop.balance = 100;
+        shop.balance = game_state.balance;
     });
-    
+    TASKS.with(|tasks| {
+        *tasks.borrow_mut() = game_state.tasks.clone(); // 新增
+    });
+    QUESTS.with(|quests| {
+        *quests.borrow_mut() = game_state.quests;
+    });
+    STATS.with(|stats| {
+        *stats.borrow_mut() = game_state.statistics;
+    });
+    UNDO.with(|history| history.borrow_mut().clear());
+    BUG_PROTECTION_ENABLED.with(|flag| *flag.borrow_mut() = game_state.bug_protection);
+    RULES.with(|rules| *rules.borrow_mut() = game_state.rules);
+    SELECTED_FERTILIZER.with(|f| *f.borrow_mut() = game_state.selected_fertilizer);
+}
+
+// 清除保存
+#[wasm_bindgen]
+pub fn clear_save() -> Result<(), JsValue> {
+    let storage = window().unwrap().local_storage()?.unwrap();
+    storage.remove_item("farm_game_state")?;
+    storage.remove_item("farm_game_action_log")?;
+    reset_game(rand::random());
     Ok(())
 }
 
+// 重置为新游戏，使用指定的随机种子开始新的操作日志
+fn reset_game(seed: u64) {
+    FARM.with(|farm| *farm.borrow_mut() = Farm::new(10, 10));
+    SHOP.with(|shop| shop.borrow_mut().balance = 100);
+    TASKS.with(|tasks| *tasks.borrow_mut() = task::default_tasks());
+    QUESTS.with(|quests| *quests.borrow_mut() = QuestLog::new());
+    STATS.with(|stats| *stats.borrow_mut() = Statistics::new());
+    UNDO.with(|history| history.borrow_mut().clear());
+    BUG_PROTECTION_ENABLED.with(|flag| *flag.borrow_mut() = false);
+    SELECTED_FERTILIZER.with(|f| *f.borrow_mut() = default_fertilizer());
+    RULES.with(|rules| *rules.borrow_mut() = RuleBook::new());
+    ACTION_LOG.with(|log| *log.borrow_mut() = ActionLog::new(seed));
+}
+
+// 渲染循环中自我调度的闭包
+type LoopClosure = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
 // 开始渲染循环
 fn start_render_loop() -> Result<(), JsValue> {
     let win = window().unwrap();
@@ -511,7 +1116,7 @@ fn start_render_loop() -> Result<(), JsValue> {
         .dyn_into::<CanvasRenderingContext2d>()?;
     let size: usize = 40;
 
-    let f: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+    let f: LoopClosure = Rc::new(RefCell::new(None));
     let f_clone = f.clone();
     let closure_ctx = ctx.clone();
 
@@ -523,438 +1128,1267 @@ fn start_render_loop() -> Result<(), JsValue> {
             TICK_COUNT += 1;
             if TICK_COUNT >= 60 {
                 TICK_COUNT = 0;
-                let _ = save_game();
+                autosave();
             }
         }
-        for row in 0..10 {
-            for col in 0..10 {
-                let state = get_state(row, col);
-        
-                // ✅ 判断虫害状态，设置背景色
-                let is_infested = state.starts_with("infested_");
-                let bg_color = if is_infested { "#444" } else { "#ddd" };
-        
-                closure_ctx.set_fill_style(&JsValue::from_str(bg_color));
-                closure_ctx.fill_rect(
+        let snapshot = farm_snapshot();
+        for (index, tile) in snapshot.tiles.iter().enumerate() {
+            let (row, col) = (index / snapshot.cols, index % snapshot.cols);
+
+            // ✅ 判断虫害状态，设置背景色
+            let bg_color = match tile.state {
+                TileStatus::Infested | TileStatus::Locked => "#444",
+                _ => "#ddd",
+            };
+
+            closure_ctx.set_fill_style_str(bg_color);
+            closure_ctx.fill_rect(
+                (col * size) as f64,
+                (row * size) as f64,
+                (size - 2) as f64,
+                (size - 2) as f64,
+            );
+
+            // ✅ 生长中和虫害的作物按品级显示种子，成熟的作物显示作物图片
+            let image = match (tile.state, tile.crop) {
+                (TileStatus::Planted | TileStatus::Infested, Some(crop)) => match crop {
+                    CropType::Wheat | CropType::Corn | CropType::Carrot | CropType::Strawberry => SEED_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::PremiumWheat | CropType::PremiumCorn | CropType::PremiumCarrot | CropType::Apple => {
+                        PREMIUM_SEED_IMAGE.with(|img| img.borrow().clone())
+                    }
+                    CropType::GoldenWheat | CropType::GoldenCorn | CropType::GoldenCarrot => GOLD_SEED_IMAGE.with(|img| img.borrow().clone()),
+                },
+                (TileStatus::Mature, Some(crop)) => match crop {
+                    CropType::Wheat => WHEAT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::PremiumWheat => PREMIUM_WHEAT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::GoldenWheat => GOLDEN_WHEAT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::Corn => CORN_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::PremiumCorn => PREMIUM_CORN_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::GoldenCorn => GOLDEN_CORN_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::Carrot => CARROT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::PremiumCarrot => PREMIUM_CARROT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::GoldenCarrot => GOLDEN_CARROT_IMAGE.with(|img| img.borrow().clone()),
+                    CropType::Strawberry | CropType::Apple => None,
+                },
+                _ => None,
+            };
+
+            if let Some(img) = image {
+                let _ = closure_ctx.draw_image_with_html_image_element_and_dw_and_dh(
+                    &img,
                     (col * size) as f64,
                     (row * size) as f64,
                     (size - 2) as f64,
                     (size - 2) as f64,
                 );
-        
-                // ✅ 为虫害或正常状态统一提取图像名
-                let image = match state.as_str() {
-                    "planted_wheat" | "infested_wheat" => SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_premium_wheat" | "infested_premium_wheat" => PREMIUM_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_golden_wheat" | "infested_golden_wheat" => GOLD_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    
-                    "planted_corn"  | "infested_corn"  => SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_premium_corn" | "infested_premium_corn" => PREMIUM_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_golden_corn" | "infested_golden_corn" => GOLD_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    
-                    "planted_carrot"| "infested_carrot"=> SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_premium_carrot" | "infested_premium_carrot" => PREMIUM_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    "planted_golden_carrot" | "infested_golden_carrot" => GOLD_SEED_IMAGE.with(|img| img.borrow().clone()),
-                    
-                    "mature_wheat" => WHEAT_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_premium_wheat" => PREMIUM_WHEAT_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_golden_wheat" => GOLDEN_WHEAT_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_corn" => CORN_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_premium_corn" => PREMIUM_CORN_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_golden_corn" => GOLDEN_CORN_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_carrot" => CARROT_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_premium_carrot" => PREMIUM_CARROT_IMAGE.with(|img| img.borrow().clone()),
-                    "mature_golden_carrot" => GOLDEN_CARROT_IMAGE.with(|img| img.borrow().clone()),
-                    _ => None,
-                };
-        
-                if let Some(img) = image {
-                    let _ = closure_ctx.draw_image_with_html_image_element_and_dw_and_dh(
-                        &img,
-                        (col * size) as f64,
-                        (row * size) as f64,
-                        (size - 2) as f64,
-                        (size - 2) as f64,
-                    );
+            }
+
+            // 多季作物、加工机器和建筑暂无贴图，使用表情符号绘制
+            let emoji = match (tile.state, tile.crop, tile.building) {
+                (TileStatus::Mature, Some(CropType::Strawberry), _) => Some("🍓"),
+                (TileStatus::Mature, Some(CropType::Apple), _) => Some("🍎"),
+                (TileStatus::Locked, _, _) => Some("🔒"),
+                (_, _, Some(ItemId::Machine(machine))) => Some(machine.icon()),
+                (_, _, Some(ItemId::Structure(structure))) => Some(structure.icon()),
+                (_, _, Some(ItemId::Pen(animal))) => Some(animal.icon()),
+                _ => None,
+            };
+            if let Some(emoji) = emoji {
+                closure_ctx.set_font("28px serif");
+                let _ = closure_ctx.fill_text(
+                    emoji,
+                    (col * size + 4) as f64,
+                    (row * size + 30) as f64,
+                );
+            }
+        }
+
+        // 渲染库存
+        if let Some(inventory_el) = document.get_element_by_id("inventory") {
+            let inventory_el = inventory_el.dyn_into::<HtmlElement>().unwrap();
+            if inventory_el.class_list().contains("active") {
+                let FarmSnapshot { balance, seeds, crops, fertilizers, goods, buildings, items, .. } = snapshot;
+                let capacity = FARM.with(|farm| farm.borrow().inventory.capacity_info());
+
+                // 渲染库存HTML
+                let mut inventory_html = format!(
+                    r#"
+                    <div class="balance">{}</div>
+                    <div class="capacity">{}</div>
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                    tf!("common.balance", amount = i18n::number(balance)),
+                    tf!(
+                        "inventory.capacity",
+                        backpack_used = capacity.backpack_used,
+                        backpack_capacity = capacity.backpack_capacity,
+                        backpack_free = capacity.backpack_capacity.saturating_sub(capacity.backpack_used),
+                        storage_used = capacity.storage_used,
+                        storage_capacity = capacity.storage_capacity,
+                        storage_free = capacity.storage_capacity.saturating_sub(capacity.storage_used)
+                    ),
+                    t("inventory.seeds"),
+                    seeds.iter().map(|(item, count)| {
+                        let img_src = match item.id() {
+                            "wheat" => "wheat.png",
+                            "premium_wheat" => "premium_wheat.png",
+                            "golden_wheat" => "golden_wheat.png",
+                            "corn" => "corn.png",
+                            "premium_corn" => "premium_corn.png",
+                            "golden_corn" => "golden_corn.png",
+                            "carrot" => "carrot.png",
+                            "premium_carrot" => "premium_carrot.png",
+                            "golden_carrot" => "golden_carrot.png",
+                            _ => "seed.png", // 兜底
+                        };
+                        format!(
+                            r#"<div class="inventory-item" draggable="true" data-seed-type="{}">
+                                <img src="{}" />
+                                <div>x{}</div>
+                            </div>"#,
+                            item.id(), img_src, count
+                        )
+                    }).collect::<Vec<_>>().join(""),
+                    t("inventory.crops"),
+                    crops.iter().filter_map(|(item, count)| match item {
+                        ItemId::Crop(crop) => Some((*crop, count)),
+                        _ => None,
+                    }).map(|(crop, count)| {
+                        let img_src = match crop.id() {
+                            "wheat" => "wheat.png",
+                            "premium_wheat" => "premium_wheat.png",
+                            "golden_wheat" => "golden_wheat.png",
+                            "corn" => "corn.png",
+                            "premium_corn" => "premium_corn.png",
+                            "golden_corn" => "golden_corn.png",
+                            "carrot" => "carrot.png",
+                            "premium_carrot" => "premium_carrot.png",
+                            "golden_carrot" => "golden_carrot.png",
+                            _ => "seed.png",
+                        };
+                        // 出售时先卖出最早收获的作物，显示它的新鲜度和折算后的售价
+                        let freshness = FARM.with(|farm| farm.borrow().inventory.oldest_freshness(crop)).unwrap_or(1.0);
+                        let base_price = SHOP.with(|s| s.borrow().get_crop_price(crop));
+                        let sell_price = (base_price as f32 * freshness).round() as u32;
+                        let sell_fn_call = format!("farmAction('try_sell_crop', '{}')", crop.id());
+                        let extract_fn_call = format!("farmAction('extract_seeds', '{}')", crop.id());
+                        format!(
+                            r#"<div class="inventory-item">
+                                <img src="{}" />
+                                <div>x{}</div>
+                                <div>{}</div>
+                                <button onclick="{}">{}</button>
+                                <button onclick="{}">{}</button>
+                            </div>"#,
+                            img_src,
+                            count,
+                            tf!("inventory.freshness", percent = (freshness * 100.0).round()),
+                            sell_fn_call,
+                            tf!("inventory.sell", price = i18n::number(sell_price)),
+                            extract_fn_call,
+                            t("inventory.extract_seeds")
+                        )
+                    }).collect::<Vec<_>>().join(""),
+                    t("inventory.fertilizers"),
+                    fertilizers.iter().map(|(item, count)| {
+                        let select_fn_call = format!("farmAction('select_fertilizer', '{}')", item.id());
+                        format!(
+                            r#"<div class="inventory-item">
+                                <img src="fertilizer.png" />
+                                <div>{}</div>
+                                <div>x{}</div>
+                                <button onclick="{}">{}</button>
+                            </div>"#,
+                            item.display_name(), count, select_fn_call, t("inventory.select")
+                        )
+                    }).collect::<Vec<_>>().join("")
+                );
+
+                // 渲染加工品和建筑
+                if !goods.is_empty() {
+                    inventory_html.push_str(&format!(
+                        r#"
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                        t("inventory.goods"),
+                        goods.iter().map(|(item, count)| {
+                            let sell_price = processing::find_good(item.id()).map(|g| g.price).unwrap_or(0);
+                            format!(
+                                r#"<div class="inventory-item">
+                                <div>{}</div>
+                                <div>x{}</div>
+                                <button onclick="farmAction('sell_good', '{}')">{}</button>
+                            </div>"#,
+                                item.display_name(), count, item.id(), tf!("inventory.sell", price = i18n::number(sell_price))
+                            )
+                        }).collect::<Vec<_>>().join("")
+                    ));
+                }
+                if !buildings.is_empty() {
+                    inventory_html.push_str(&format!(
+                        r#"
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                        t("inventory.buildings"),
+                        buildings.iter().map(|(item, count)| {
+                            let icon = match item {
+                                ItemId::Machine(machine) => machine.icon(),
+                                ItemId::Structure(structure) => structure.icon(),
+                                ItemId::Pen(animal) => animal.icon(),
+                                _ => "🏠",
+                            };
+                            format!(
+                                r#"<div class="inventory-item" draggable="true" data-seed-type="building:{}">
+                                <div>{} {}</div>
+                                <div>x{}</div>
+                            </div>"#,
+                                item.id(), icon, item.display_name(), count
+                            )
+                        }).collect::<Vec<_>>().join("")
+                    ));
+                }
+
+                if !items.is_empty() {
+                    inventory_html.push_str(&format!(
+                        r#"
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                        t("inventory.items"),
+                        items.iter().map(|(item, count)| {
+                            let (icon, label) = reward::find_item(item.id())
+                                .map(|i| (i.icon, i.kind.label()))
+                                .unwrap_or(("🎁", ""));
+                            format!(
+                                r#"<div class="inventory-item">
+                                <div>{} {}</div>
+                                <div>{}</div>
+                                <div>x{}</div>
+                            </div>"#,
+                                icon, item.display_name(), label, count
+                            )
+                        }).collect::<Vec<_>>().join("")
+                    ));
+                }
+
+                // 渲染已放置的围栏
+                let pens = FARM.with(|farm| {
+                    let farm = farm.borrow();
+                    let mut pens = Vec::new();
+                    for (r, row) in farm.grid.iter().enumerate() {
+                        for (c, tile) in row.iter().enumerate() {
+                            if let Some(pen) = tile.pen() {
+                                pens.push((r, c, pen.kind, pen.animals.len()));
+                            }
+                        }
+                    }
+                    pens
+                });
+                if !pens.is_empty() {
+                    inventory_html.push_str(&format!(
+                        r#"
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                        t("inventory.pens"),
+                        pens.iter().map(|(r, c, kind, count)| {
+                            let price = SHOP.with(|s| s.borrow().animals.get(kind).copied().unwrap_or(0));
+                            format!(
+                                r#"<div class="inventory-item">
+                                <div>{} {} ({}, {})</div>
+                                <div>{}/{}</div>
+                                <button onclick="farmAction('buy_animal', {}, {})">{}</button>
+                            </div>"#,
+                                kind.icon(), kind.pen_display_name(), r, c,
+                                count, animal::PEN_CAPACITY,
+                                r, c, tf!("inventory.buy_animal", animal = kind.display_name(), price = i18n::number(price))
+                            )
+                        }).collect::<Vec<_>>().join("")
+                    ));
+                }
+
+                // 渲染制种机
+                let seed_jobs = FARM.with(|farm| farm.borrow().seed_maker.jobs.clone());
+                if !seed_jobs.is_empty() {
+                    inventory_html.push_str(&format!(
+                        r#"
+                    <div class="inventory-section">
+                        <h3>{}</h3>
+                        <div class="inventory-items">
+                            {}
+                        </div>
+                    </div>
+                    "#,
+                        t("inventory.seed_maker"),
+                        seed_jobs.iter().map(|job| {
+                            format!(
+                                r#"<div class="inventory-item">
+                                <div>{}</div>
+                                <div>{}</div>
+                            </div>"#,
+                                job.crop.display_name(), tf!("inventory.seconds_left", seconds = job.remaining)
+                            )
+                        }).collect::<Vec<_>>().join("")
+                    ));
+                }
+
+                inventory_el.set_inner_html(&inventory_html);
+
+                // 渲染种子
+                let seed_items = inventory_el.get_elements_by_class_name("inventory-item");
+                for i in 0..seed_items.length() {
+                    if let Some(item) = seed_items.get_with_index(i) {
+                        let item = item.dyn_into::<HtmlElement>().unwrap();
+                        let seed_type = item.get_attribute("data-seed-type").unwrap_or_default();
+
+                        // 添加拖拽事件
+                        let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+                            let data_transfer = event.data_transfer().unwrap();
+                            let _ = data_transfer.set_data("text/plain", &seed_type);
+                            let target = event.target().unwrap();
+                            let element = target.dyn_into::<HtmlElement>().unwrap();
+                            let _ = element.class_list().add_1("dragging");
+                        }) as Box<dyn FnMut(_)>);
+                        let _ = item.add_event_listener_with_callback("dragstart", closure.as_ref().unchecked_ref());
+                        closure.forget();
+
+                        // 添加拖拽结束事件
+                        let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+                            let target = event.target().unwrap();
+                            let element = target.dyn_into::<HtmlElement>().unwrap();
+                            let _ = element.class_list().remove_1("dragging");
+                        }) as Box<dyn FnMut(_)>);
+                        let _ = item.add_event_listener_with_callback("dragend", closure.as_ref().unchecked_ref());
+                        closure.forget();
+                    }
                 }
             }
         }
 
-        // 渲染库存
-        if let Some(inventory_el) = document.get_element_by_id("inventory") {
-            let inventory_el = inventory_el.dyn_into::<HtmlElement>().unwrap();
-            if inventory_el.class_list().contains("active") {
-                let (seeds, crops, fertilizers) = FARM.with(|farm| farm.borrow().get_full_inventory());
-                let balance = SHOP.with(|shop| shop.borrow().get_balance());
+        // 渲染商店
+        if let Some(shop_el) = document.get_element_by_id("shop-items") {
+            let shop_html = SHOP.with(|shop| render_shop(&shop.borrow()));
+            shop_el.set_inner_html(&shop_html);
+        }
+
+        // 设置定时器
+        let _ = window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(
+            f_clone
+                .borrow()
+                .as_ref()
+                .unwrap()
+                .as_ref()
+                .unchecked_ref(),
+            1000,
+        );
+    }) as Box<dyn FnMut()>);
+
+    f.borrow_mut().replace(closure);
+    let _ = window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(
+        f.borrow().as_ref().unwrap().as_ref().unchecked_ref(),
+        1000,
+    );
+
+    Ok(())
+}
+
+// 生成商店页面，未通过等级或任务链解锁的物品显示为锁定
+fn render_shop(shop: &Shop) -> String {
+    let sections = shop::SHOP_SECTIONS.iter().map(|section| {
+        let items = section
+            .items
+            .iter()
+            .filter_map(|id| render_shop_item(shop, section.buy_fn, id))
+            .collect::<String>();
+        format!(
+            r#"
+                <div class="shop-section">
+                    <h3>{}</h3>
+                    <div class="shop-items-grid">{}
+                    </div>
+                </div>"#,
+            t(section.title),
+            items
+        )
+    });
+    format!(
+        r#"
+                <div class="balance">{}</div>{}
+                "#,
+        tf!("common.balance", amount = i18n::number(shop.get_balance())),
+        sections.collect::<String>()
+    )
+}
+
+// 生成商店中的一件商品，商店不出售的物品返回 None
+fn render_shop_item(shop: &Shop, buy_fn: &str, id: &str) -> Option<String> {
+    let item = ItemId::parse(id).ok()?;
+    let price = shop.list_price(item)?;
+    let (image, name, description) = match item {
+        ItemId::Crop(crop) => {
+            let image = match crop {
+                CropType::Strawberry => "seed.png".to_string(),
+                CropType::Apple => "premium_seed.png".to_string(),
+                _ => format!("{}.png", crop.id()),
+            };
+            let description = if crop.is_perennial() {
+                tf!("shop.harvests", count = crop.max_harvests())
+            } else {
+                String::new()
+            };
+            (Some(image), tf!("item.seed_name", name = crop.display_name()), description)
+        }
+        ItemId::Fertilizer(fertilizer) => (
+            Some("fertilizer.png".to_string()),
+            fertilizer.display_name().to_string(),
+            t(&format!("fertilizer.{}.shop", fertilizer.id())).to_string(),
+        ),
+        ItemId::Machine(machine) => {
+            (None, format!("{} {}", machine.icon(), machine.display_name()), machine.description().to_string())
+        }
+        ItemId::Structure(structure) => (
+            None,
+            format!("{} {}", structure.icon(), structure.display_name()),
+            structure.shop_description().to_string(),
+        ),
+        ItemId::Pen(animal) => {
+            (None, format!("{} {}", animal.icon(), animal.pen_display_name()), animal.pen_description().to_string())
+        }
+        ItemId::Good(_) | ItemId::Item(_) => return None,
+    };
+
+    let image = image.map(|src| format!(r#"<img src="{}" />"#, src)).unwrap_or_default();
+    let description = if description.is_empty() {
+        String::new()
+    } else {
+        format!(r#"<div class="description">{}</div>"#, description)
+    };
+    let button = if is_item_unlocked(id) {
+        format!(r#"<button onclick="farmAction('{}', '{}')">{}</button>"#, buy_fn, id, t("shop.buy"))
+    } else {
+        let label = match progression::required_level(id) {
+            Some(level) => tf!("shop.level_locked", level = level),
+            None => t("shop.quest_locked").to_string(),
+        };
+        format!(r#"<button onclick="farmAction('{}', '{}')" disabled>{}</button>"#, buy_fn, id, label)
+    };
+    Some(format!(
+        r#"
+                        <div class="shop-item">
+                            {}
+                            <div>{}</div>
+                            <div class="price">{}</div>
+                            {}
+                            {}
+                        </div>"#,
+        image,
+        name,
+        tf!("shop.price", price = i18n::number(price)),
+        description,
+        button
+    ))
+}
+
+// 加载图片
+fn load_image(src: &str, setter: fn(HtmlImageElement)) -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let img = document.create_element("img")?.dyn_into::<HtmlImageElement>()?;
+
+    let img_clone = img.clone();
+    let closure = Closure::wrap(Box::new(move || {
+        setter(img_clone.clone());
+        LOADED_COUNT.with(|count| {
+            let mut count = count.borrow_mut();
+            *count += 1;
+            if *count == 10 {
+                start_render_loop().unwrap();
+            }
+        });
+    }) as Box<dyn FnMut()>);
+
+    img.set_onload(Some(closure.as_ref().unchecked_ref()));
+    img.set_src(src);
+    closure.forget();
+
+    Ok(())
+}
+
+// 尝试出售作物
+fn sell_crop_action(crop_type: String) -> Result<(), GameError> {
+    let crop = item::parse_crop(&crop_type)?;
+    let checkpoint = checkpoint(None);
+    // 先卖出最早收获的作物，售价按新鲜度折算
+    let freshness = FARM
+        .with(|farm| farm.borrow_mut().inventory.take_crop(crop))
+        .ok_or(GameError::NoItemInInventory { item: crop_type })?;
+    let revenue = SHOP.with(|shop| shop.borrow_mut().sell_crop(crop, freshness));
+    record_task_event(TaskEvent::Sold(crop));
+    record_task_event(TaskEvent::Earned(revenue));
+    record_stat(StatEvent::Sold { crop, revenue });
+    record_action(ActionKind::Sell, checkpoint);
+    autosave();
+    play_sound("sell_crop.wav");
+    Ok(())
+}
+
+// 把作物放入制种机
+fn extract_seeds_action(crop_type: String) -> Result<(), GameError> {
+    FARM.with(|farm| farm.borrow_mut().extract_seeds(&crop_type))?;
+    play_sound("click.wav");
+    crate::utils::show_message(t("message.seeds_extracting"));
+    autosave();
+    Ok(())
+}
+
+// 购买放入背包等待放置的机器、建筑或围栏
+fn buy_building(item: ItemId, buy: impl FnOnce(&mut Shop) -> Result<(), GameError>, message_key: &str) -> Result<(), GameError> {
+    check_unlocked(item.id())?;
+    let checkpoint = checkpoint(None);
+    let balance_before = get_balance();
+    SHOP.with(|shop| buy(&mut shop.borrow_mut()))?;
+    FARM.with(|farm| farm.borrow_mut().inventory.add_building(item));
+    record_action(ActionKind::Buy, checkpoint);
+    let cost = balance_before - get_balance();
+    record_stat(StatEvent::Spent(cost));
+    play_sound("sell_crop.wav");
+    crate::utils::show_message(t(message_key));
+    autosave();
+    Ok(())
+}
+
+// 放置背包中的机器、建筑或围栏
+fn place_building(place: impl FnOnce(&mut Farm) -> Result<(), GameError>) -> Result<(), GameError> {
+    FARM.with(|farm| place(&mut farm.borrow_mut()))?;
+    play_sound("click.wav");
+    autosave();
+    Ok(())
+}
+
+// 购买加工机器，放入背包等待放置
+fn buy_machine_action(machine_type: String) -> Result<(), GameError> {
+    let machine = item::parse_machine(&machine_type)?;
+    buy_building(ItemId::Machine(machine), |shop| shop.buy_machine(machine), "message.machine_bought")
+}
+
+// 放置加工机器
+fn place_machine_action(row: usize, col: usize, machine_type: String) -> Result<(), GameError> {
+    place_building(|farm| farm.place_machine(row, col, &machine_type))
+}
+
+// 购买建筑，放入背包等待放置
+fn buy_structure_action(structure_type: String) -> Result<(), GameError> {
+    let structure = item::parse_structure(&structure_type)?;
+    buy_building(ItemId::Structure(structure), |shop| shop.buy_structure(structure), "message.structure_bought")
+}
+
+// 放置建筑，多格建筑以该地块为左上角
+fn place_structure_action(row: usize, col: usize, structure_type: String) -> Result<(), GameError> {
+    place_building(|farm| farm.place_structure(row, col, &structure_type))
+}
+
+// 购买动物围栏，放入背包等待放置
+fn buy_pen_action(pen_type: String) -> Result<(), GameError> {
+    let animal = item::parse_pen(&pen_type)?;
+    buy_building(ItemId::Pen(animal), |shop| shop.buy_pen(animal), "message.pen_bought")
+}
+
+// 放置动物围栏
+fn place_pen_action(row: usize, col: usize, pen_type: String) -> Result<(), GameError> {
+    place_building(|farm| farm.place_pen(row, col, &pen_type))
+}
+
+// 购买一只动物放入指定地块的围栏
+fn buy_animal_action(row: usize, col: usize) -> Result<(), GameError> {
+    let kind = FARM.with(|farm| farm.borrow().pen_with_room(row, col))?;
+    let balance_before = get_balance();
+    SHOP.with(|shop| shop.borrow_mut().buy_animal(kind))?;
+    record_stat(StatEvent::Spent(balance_before - get_balance()));
+    FARM.with(|farm| farm.borrow_mut().add_animal(row, col));
+    play_sound("sell_crop.wav");
+    crate::utils::show_message(&tf!("message.animal_bought", icon = kind.icon(), animal = kind.display_name()));
+    autosave();
+    Ok(())
+}
+
+// 用库存中的作物喂食围栏中的动物
+fn feed_animals_action(row: usize, col: usize) -> Result<u32, GameError> {
+    let fed = FARM.with(|farm| farm.borrow_mut().feed_pen(row, col))?;
+    play_sound("click.wav");
+    crate::utils::show_message(&tf!("message.animals_fed", count = fed));
+    autosave();
+    Ok(fed)
+}
+
+// 在机器上开始加工，未指定原料时自动选择库存足够的配方
+fn start_processing_action(row: usize, col: usize, input: Option<String>) -> Result<(), GameError> {
+    let input = input.as_deref().map(item::parse_crop).transpose()?;
+    FARM.with(|farm| farm.borrow_mut().start_processing(row, col, input))?;
+    play_sound("click.wav");
+    crate::utils::show_message(t("message.processing_started"));
+    autosave();
+    Ok(())
+}
+
+// 出售加工品
+fn sell_good_action(good: String) -> Result<(), GameError> {
+    let id = item::parse_good(&good)?;
+    let checkpoint = checkpoint(None);
+    if !FARM.with(|farm| farm.borrow_mut().inventory.remove_good(ItemId::Good(id))) {
+        return Err(GameError::NoItemInInventory { item: good });
+    }
+    let revenue = SHOP.with(|shop| {
+        let mut shop = shop.borrow_mut();
+        shop.sell_good(&good);
+        shop.get_good_price(&good).unwrap_or(0)
+    });
+    record_task_event(TaskEvent::Earned(revenue));
+    record_stat(StatEvent::GoodSold { revenue });
+    record_action(ActionKind::Sell, checkpoint);
+    play_sound("sell_crop.wav");
+    autosave();
+    Ok(())
+}
+
+// 获取制种机状态
+#[wasm_bindgen]
+pub fn get_seed_maker() -> JsValue {
+    FARM.with(|farm| {
+        serde_wasm_bindgen::to_value(&farm.borrow().seed_maker).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+// 获取任务
+pub fn get_tasks() -> JsValue {
+    TASKS.with(|tasks| {
+        let tasks = tasks.borrow().iter().map(Task::localized).collect::<Vec<_>>();
+        serde_wasm_bindgen::to_value(&tasks).unwrap()
+    })
+}
+
+// 领取任务奖励
+fn claim_task_reward_action(task_id: u32) -> Result<(), GameError> {
+    TASKS.with(|tasks| {
+        let mut tasks = tasks.borrow_mut();
+        let pos = tasks
+            .iter()
+            .position(|t| t.id == task_id && t.completed && !t.claimed)
+            .ok_or(GameError::TaskNotClaimable { task_id })?;
+        let task = &mut tasks[pos];
+        let summary = FARM.with(|farm| {
+            SHOP.with(|shop| reward::grant(task.reward, &task.extras, &mut farm.borrow_mut(), &mut shop.borrow_mut()))
+        })?;
+        crate::utils::show_message(&summary);
+        task.claimed = true;
+        // 生成新任务并替换原任务
+        let new_id = tasks.iter().map(|t| t.id).max().unwrap_or(0);
+        let day = FARM.with(|farm| farm.borrow().day());
+        let new_task = task::generate_new_task(new_id, day);
+        tasks[pos] = new_task;
+        Ok(())
+    })?;
+    // 奖励无法撤销，清空之前的记录，避免撤销后再次领取
+    UNDO.with(|history| history.borrow_mut().clear());
+    autosave();
+    play_sound("sell_crop.wav");
+    Ok(())
+}
+
+// 撤销最近一次操作，操作过去太久或状态已变化时无法撤销
+fn undo_action() -> Result<(), GameError> {
+    let balance = get_balance();
+    // 先检查最近的记录，无法撤销时保留在历史中
+    UNDO.with(|history| {
+        let history = history.borrow();
+        let entry = history.last().ok_or(GameError::NothingToUndo)?;
+        FARM.with(|farm| entry.check(&farm.borrow(), balance))
+    })?;
+    let entry = UNDO.with(|history| history.borrow_mut().pop()).ok_or(GameError::NothingToUndo)?;
+    let kind = entry.kind;
+    let balance = FARM.with(|farm| entry.revert(&mut farm.borrow_mut(), balance));
+    SHOP.with(|shop| shop.borrow_mut().balance = balance);
+    TASKS.with(|tasks| entry.revert_tasks(&mut tasks.borrow_mut()));
+    QUESTS.with(|quests| entry.revert_quests(&mut quests.borrow_mut()));
+    STATS.with(|stats| entry.revert_statistics(&mut stats.borrow_mut()));
+    ACHIEVEMENTS.with(|achievements| entry.revert_achievements(&mut achievements.borrow_mut()));
+    crate::utils::show_message(&tf!("message.undone", action = kind.display_name()));
+    play_sound("click.wav");
+    autosave();
+    Ok(())
+}
+
+#[wasm_bindgen]
+// 获取经济统计数据，包括按天和按作物的汇总
+pub fn get_statistics() -> JsValue {
+    STATS.with(|stats| {
+        serde_wasm_bindgen::to_value(&stats.borrow().report()).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+// 获取所有成就及解锁状态
+pub fn get_achievements() -> JsValue {
+    ACHIEVEMENTS.with(|achievements| {
+        serde_wasm_bindgen::to_value(&achievements.borrow().views()).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+// 取出新解锁的成就，界面据此弹出提示
+pub fn take_achievement_events() -> JsValue {
+    ACHIEVEMENTS.with(|achievements| {
+        serde_wasm_bindgen::to_value(&achievements.borrow_mut().take_pending()).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+// 重置成就，只有玩家主动重置时才会清除
+pub fn reset_achievements() -> Result<(), JsValue> {
+    ACHIEVEMENTS.with(|achievements| *achievements.borrow_mut() = AchievementTracker::new());
+    let storage = window().unwrap().local_storage()?.unwrap();
+    storage.remove_item("farm_game_achievements")?;
+    Ok(())
+}
+
+#[wasm_bindgen]
+// 获取等级和经验信息
+pub fn get_level_info() -> JsValue {
+    FARM.with(|farm| {
+        let farm = farm.borrow();
+        serde_wasm_bindgen::to_value(&progression::level_info(farm.level, farm.xp)).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+// 获取进行中的任务链
+pub fn get_quests() -> JsValue {
+    QUESTS.with(|quests| {
+        serde_wasm_bindgen::to_value(&quests.borrow().views()).unwrap()
+    })
+}
+
+// 领取任务链当前步骤的奖励
+fn claim_quest_step_action(chain_id: String) -> Result<(), GameError> {
+    let claim = QUESTS
+        .with(|quests| quests.borrow_mut().claim(&chain_id))
+        .ok_or(GameError::QuestStepNotReady { chain_id })?;
+    SHOP.with(|shop| shop.borrow_mut().balance += claim.reward);
+    gain_xp(claim.reward / 2);
+    // 奖励无法撤销，清空之前的记录，避免撤销后再次领取
+    UNDO.with(|history| history.borrow_mut().clear());
+    if let Some(chain) = claim.finished_chain {
+        let message = if chain.unlocks.is_empty() {
+            tf!("message.quest_finished", name = chain.name())
+        } else {
+            tf!("message.quest_finished_unlocks", name = chain.name())
+        };
+        crate::utils::show_message(&message);
+    }
+    autosave();
+    play_sound("sell_crop.wav");
+    Ok(())
+}
+
+// 玩家操作入口，全部通过 execute 执行并记入操作日志
+
+#[wasm_bindgen]
+pub fn apply_bug_protection() -> Result<(), JsValue> {
+    run(Command::ApplyBugProtection)
+}
+
+#[wasm_bindgen]
+pub fn spray_tile(row: usize, col: usize) -> Result<(), JsValue> {
+    run(Command::SprayTile { row, col })
+}
+
+#[wasm_bindgen]
+pub fn plant(row: usize, col: usize, crop: String) -> Result<(), JsValue> {
+    run(Command::Plant { row, col, crop })
+}
+
+#[wasm_bindgen]
+pub fn harvest(row: usize, col: usize) -> Result<(), JsValue> {
+    run(Command::Harvest { row, col })
+}
+
+#[wasm_bindgen]
+pub fn fertilize(row: usize, col: usize) -> Result<(), JsValue> {
+    let fertilizer = SELECTED_FERTILIZER.with(|f| f.borrow().clone());
+    run(Command::Fertilize { row, col, fertilizer: Some(fertilizer) })
+}
+
+#[wasm_bindgen]
+pub fn select_fertilizer(fertilizer_type: String) -> Result<(), JsValue> {
+    run(Command::SelectFertilizer { fertilizer: fertilizer_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_fertilizer(fertilizer_type: String) -> Result<(), JsValue> {
+    run(Command::BuyFertilizer { fertilizer: fertilizer_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_seed(seed_type: String) -> Result<(), JsValue> {
+    run(Command::BuySeed { seed: seed_type })
+}
+
+#[wasm_bindgen]
+pub fn clear_tile(row: usize, col: usize) -> Result<(), JsValue> {
+    run(Command::ClearTile { row, col })
+}
+
+#[wasm_bindgen]
+pub fn try_sell_crop(crop_type: String) -> Result<(), JsValue> {
+    run(Command::SellCrop { crop: crop_type })
+}
+
+// 出售作物，与 try_sell_crop 相同
+#[wasm_bindgen]
+pub fn sell_crop(crop_type: String) -> Result<(), JsValue> {
+    run(Command::SellCrop { crop: crop_type })
+}
+
+#[wasm_bindgen]
+pub fn extract_seeds(crop_type: String) -> Result<(), JsValue> {
+    run(Command::ExtractSeeds { crop: crop_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_machine(machine_type: String) -> Result<(), JsValue> {
+    run(Command::BuyMachine { machine: machine_type })
+}
+
+#[wasm_bindgen]
+pub fn place_machine(row: usize, col: usize, machine_type: String) -> Result<(), JsValue> {
+    run(Command::PlaceMachine { row, col, machine: machine_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_structure(structure_type: String) -> Result<(), JsValue> {
+    run(Command::BuyStructure { structure: structure_type })
+}
+
+#[wasm_bindgen]
+pub fn place_structure(row: usize, col: usize, structure_type: String) -> Result<(), JsValue> {
+    run(Command::PlaceStructure { row, col, structure: structure_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_pen(pen_type: String) -> Result<(), JsValue> {
+    run(Command::BuyPen { pen: pen_type })
+}
+
+#[wasm_bindgen]
+pub fn place_pen(row: usize, col: usize, pen_type: String) -> Result<(), JsValue> {
+    run(Command::PlacePen { row, col, pen: pen_type })
+}
+
+#[wasm_bindgen]
+pub fn buy_animal(row: usize, col: usize) -> Result<(), JsValue> {
+    run(Command::BuyAnimal { row, col })
+}
+
+#[wasm_bindgen]
+pub fn feed_animals(row: usize, col: usize) -> Result<u32, JsValue> {
+    js_result(execute(Command::FeedAnimals { row, col }).map(|outcome| outcome.count()))
+}
+
+#[wasm_bindgen]
+pub fn start_processing(row: usize, col: usize, input: Option<String>) -> Result<(), JsValue> {
+    run(Command::StartProcessing { row, col, input })
+}
+
+#[wasm_bindgen]
+pub fn sell_good(good: String) -> Result<(), JsValue> {
+    run(Command::SellGood { good })
+}
+
+#[wasm_bindgen]
+pub fn claim_task_reward(task_id: u32) -> Result<(), JsValue> {
+    run(Command::ClaimTaskReward { task_id })
+}
+
+#[wasm_bindgen]
+pub fn undo_last_action() -> Result<(), JsValue> {
+    run(Command::Undo)
+}
+
+// 批量操作返回结果汇总
+fn execute_area(command: Command) -> Result<JsValue, JsValue> {
+    match js_result(execute(command))? {
+        Outcome::Area(summary) => Ok(serde_wasm_bindgen::to_value(&summary)?),
+        _ => Ok(JsValue::NULL),
+    }
+}
+
+#[wasm_bindgen]
+pub fn harvest_all() -> Result<JsValue, JsValue> {
+    execute_area(Command::HarvestAll)
+}
+
+#[wasm_bindgen]
+pub fn plant_rect(r0: usize, c0: usize, r1: usize, c1: usize, crop: String) -> Result<JsValue, JsValue> {
+    execute_area(Command::PlantRect { r0, c0, r1, c1, crop })
+}
+
+#[wasm_bindgen]
+pub fn fertilize_rect(r0: usize, c0: usize, r1: usize, c1: usize) -> Result<JsValue, JsValue> {
+    let fertilizer = SELECTED_FERTILIZER.with(|f| f.borrow().clone());
+    execute_area(Command::FertilizeRect { r0, c0, r1, c1, fertilizer: Some(fertilizer) })
+}
+
+#[wasm_bindgen]
+pub fn spray_all_infested() -> Result<JsValue, JsValue> {
+    execute_area(Command::SprayAllInfested)
+}
+
+#[wasm_bindgen]
+pub fn hire_worker(role: String, crop: Option<String>, r0: usize, c0: usize, r1: usize, c1: usize) -> Result<(), JsValue> {
+    run(Command::HireWorker { role, crop, r0, c0, r1, c1 })
+}
+
+#[wasm_bindgen]
+pub fn fire_worker(worker_id: u32) -> Result<(), JsValue> {
+    run(Command::FireWorker { worker_id })
+}
+
+// 添加规则，规则中的作物无效时直接报告错误
+fn add_rule(rule: Result<Rule, GameError>) -> Result<(), JsValue> {
+    match rule {
+        Ok(rule) => run(Command::AddRule { rule }),
+        Err(err) => {
+            report_error(&err);
+            js_result(Err(err))
+        }
+    }
+}
+
+#[wasm_bindgen]
+// 添加规则：指定作物成熟时自动收获并补种
+pub fn add_replant_rule(crop: String) -> Result<(), JsValue> {
+    add_rule(item::parse_crop(&crop).map(|crop| Rule::AutoReplant { crop }))
+}
 
-                // 渲染库存HTML
-                let inventory_html = format!(
-                    r#"
-                    <div class="balance">金币: {}</div>
-                    <div class="inventory-section">
-                        <h3>种子</h3>
-                        <div class="inventory-items">
-                            {}
-                        </div>
-                    </div>
-                    <div class="inventory-section">
-                        <h3>农作物</h3>
-                        <div class="inventory-items">
-                            {}
-                        </div>
-                    </div>
-                    <div class="inventory-section">
-                        <h3>肥料</h3>
-                        <div class="inventory-items">
-                            {}
-                        </div>
-                    </div>
-                    "#,
-                    balance,
-                    seeds.iter().map(|(item, count)| {
-                        let img_src = match item.as_str() {
-                            "wheat" => "wheat.png",
-                            "premium_wheat" => "premium_wheat.png",
-                            "golden_wheat" => "golden_wheat.png",
-                            "corn" => "corn.png",
-                            "premium_corn" => "premium_corn.png",
-                            "golden_corn" => "golden_corn.png",
-                            "carrot" => "carrot.png",
-                            "premium_carrot" => "premium_carrot.png",
-                            "golden_carrot" => "golden_carrot.png",
-                            _ => "seed.png", // 兜底
-                        };
-                        format!(
-                            r#"<div class="inventory-item" draggable="true" data-seed-type="{}">
-                                <img src="{}" />
-                                <div>x{}</div>
-                            </div>"#,
-                            item, img_src, count
-                        )
-                    }).collect::<Vec<_>>().join(""),
-                    crops.iter().map(|(item, count)| {
-                        let img_src = match item.as_str() {
-                            "wheat" => "wheat.png",
-                            "premium_wheat" => "premium_wheat.png",
-                            "golden_wheat" => "golden_wheat.png",
-                            "corn" => "corn.png",
-                            "premium_corn" => "premium_corn.png",
-                            "golden_corn" => "golden_corn.png",
-                            "carrot" => "carrot.png",
-                            "premium_carrot" => "premium_carrot.png",
-                            "golden_carrot" => "golden_carrot.png",
-                            _ => item,
-                        };
-                        let sell_price = SHOP.with(|s| s.borrow().get_crop_price(item).unwrap_or(0));
-                        let sell_fn_call = format!("window.wasmBindings.try_sell_crop('{}')", item);
-                        format!(
-                            r#"<div class="inventory-item">
-                                <img src="{}" />
-                                <div>x{}</div>
-                                <button onclick="{}">出售 ({}金币)</button>
-                            </div>"#,
-                            img_src, count, sell_fn_call, sell_price
-                        )
-                    }).collect::<Vec<_>>().join(""),
-                    fertilizers.iter().map(|(item, count)| {
-                        let display_name = match item.as_str() {
-                            "basic_fertilizer" => "基础肥料",
-                            "premium_fertilizer" => "高级肥料",
-                            "super_fertilizer" => "超级肥料",
-                            _ => item,
-                        };
-                        let select_fn_call = format!("window.wasmBindings.select_fertilizer('{}')", item);
-                        format!(
-                            r#"<div class="inventory-item">
-                                <img src="fertilizer.png" />
-                                <div>{}</div>
-                                <div>x{}</div>
-                                <button onclick="{}">选择</button>
-                            </div>"#,
-                            display_name, count, select_fn_call
-                        )
-                    }).collect::<Vec<_>>().join("")
-                );
+#[wasm_bindgen]
+// 添加规则：作物超过阈值时自动出售
+pub fn add_sell_rule(crop: String, threshold: u32) -> Result<(), JsValue> {
+    add_rule(item::parse_crop(&crop).map(|crop| Rule::SellAbove { crop, threshold }))
+}
 
-                inventory_el.set_inner_html(&inventory_html);
+#[wasm_bindgen]
+// 添加规则：种子少于阈值且金币充足时自动购买
+pub fn add_buy_seed_rule(seed: String, threshold: u32, amount: u32, min_balance: u32) -> Result<(), JsValue> {
+    add_rule(item::parse_crop(&seed).map(|seed| Rule::BuySeedsBelow { seed, threshold, amount, min_balance }))
+}
 
-                // 渲染种子
-                let seed_items = inventory_el.get_elements_by_class_name("inventory-item");
-                for i in 0..seed_items.length() {
-                    if let Some(item) = seed_items.get_with_index(i) {
-                        let item = item.dyn_into::<HtmlElement>().unwrap();
-                        let seed_type = item.get_attribute("data-seed-type").unwrap_or_default();
+#[wasm_bindgen]
+pub fn remove_rule(rule_id: u32) -> Result<(), JsValue> {
+    run(Command::RemoveRule { rule_id })
+}
 
-                        // 添加拖拽事件
-                        let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
-                            let data_transfer = event.data_transfer().unwrap();
-                            let _ = data_transfer.set_data("text/plain", &seed_type);
-                            let target = event.target().unwrap();
-                            let element = target.dyn_into::<HtmlElement>().unwrap();
-                            let _ = element.class_list().add_1("dragging");
-                        }) as Box<dyn FnMut(_)>);
-                        let _ = item.add_event_listener_with_callback("dragstart", closure.as_ref().unchecked_ref());
-                        closure.forget();
+#[wasm_bindgen]
+pub fn set_rule_enabled(rule_id: u32, enabled: bool) -> Result<(), JsValue> {
+    run(Command::SetRuleEnabled { rule_id, enabled })
+}
 
-                        // 添加拖拽结束事件
-                        let closure = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
-                            let target = event.target().unwrap();
-                            let element = target.dyn_into::<HtmlElement>().unwrap();
-                            let _ = element.class_list().remove_1("dragging");
-                        }) as Box<dyn FnMut(_)>);
-                        let _ = item.add_event_listener_with_callback("dragend", closure.as_ref().unchecked_ref());
-                        closure.forget();
-                    }
-                }
-            }
-        }
+#[wasm_bindgen]
+// 获取自动化规则
+pub fn get_rules() -> JsValue {
+    RULES.with(|rules| serde_wasm_bindgen::to_value(&rules.borrow().views()).unwrap())
+}
 
-        // 渲染商店
-        if let Some(shop_el) = document.get_element_by_id("shop-items") {
-            let balance = SHOP.with(|shop| shop.borrow().get_balance());
-            let shop_html = format!(
-                r#"
-                <div class="balance">金币: {}</div>
-                <div class="shop-section">
-                    <h3>基础种子</h3>
-                    <div class="shop-items-grid">
-                        <div class="shop-item">
-                            <img src="wheat.png" />
-                            <div>小麦种子</div>
-                            <div class="price">10金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('wheat')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="corn.png" />
-                            <div>玉米种子</div>
-                            <div class="price">20金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('corn')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="carrot.png" />
-                            <div>胡萝卜种子</div>
-                            <div class="price">15金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('carrot')">购买</button>
-                        </div>
-                    </div>
-                </div>
-                <div class="shop-section">
-                    <h3>高级种子</h3>
-                    <div class="shop-items-grid">
-                        <div class="shop-item">
-                            <img src="premium_wheat.png" />
-                            <div>优质小麦种子</div>
-                            <div class="price">25金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('premium_wheat')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="premium_corn.png" />
-                            <div>优质玉米种子</div>
-                            <div class="price">35金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('premium_corn')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="premium_carrot.png" />
-                            <div>优质胡萝卜种子</div>
-                            <div class="price">30金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('premium_carrot')">购买</button>
-                        </div>
-                    </div>
-                </div>
-                <div class="shop-section">
-                    <h3>特殊种子</h3>
-                    <div class="shop-items-grid">
-                        <div class="shop-item">
-                            <img src="golden_wheat.png" />
-                            <div>金色小麦种子</div>
-                            <div class="price">50金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('golden_wheat')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="golden_corn.png" />
-                            <div>金色玉米种子</div>
-                            <div class="price">60金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('golden_corn')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="golden_carrot.png" />
-                            <div>金色胡萝卜种子</div>
-                            <div class="price">55金币</div>
-                            <button onclick="window.wasmBindings.buy_seed('golden_carrot')">购买</button>
-                        </div>
-                    </div>
-                </div>
-                <div class="shop-section">
-                    <h3>肥料</h3>
-                    <div class="shop-items-grid">
-                        <div class="shop-item">
-                            <img src="fertilizer.png" />
-                            <div>基础肥料</div>
-                            <div class="price">25金币</div>
-                            <div class="description">减少20%成长时间</div>
-                            <button onclick="window.wasmBindings.buy_fertilizer('basic_fertilizer')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="fertilizer.png" />
-                            <div>高级肥料</div>
-                            <div class="price">50金币</div>
-                            <div class="description">减少35%成长时间</div>
-                            <button onclick="window.wasmBindings.buy_fertilizer('premium_fertilizer')">购买</button>
-                        </div>
-                        <div class="shop-item">
-                            <img src="fertilizer.png" />
-                            <div>超级肥料</div>
-                            <div class="price">80金币</div>
-                            <div class="description">减少50%成长时间</div>
-                            <button onclick="window.wasmBindings.buy_fertilizer('super_fertilizer')">购买</button>
-                        </div>
-                    </div>
-                </div>
-                "#,
-                balance
-            );
-            shop_el.set_inner_html(&shop_html);
+#[wasm_bindgen]
+// 获取背包和仓库的容量
+pub fn get_capacity() -> JsValue {
+    FARM.with(|farm| serde_wasm_bindgen::to_value(&farm.borrow().inventory.capacity_info()).unwrap())
+}
+
+#[wasm_bindgen]
+// 获取雇佣的帮工
+pub fn get_workers() -> JsValue {
+    FARM.with(|farm| serde_wasm_bindgen::to_value(&farm.borrow().crew.views()).unwrap())
+}
+
+#[wasm_bindgen]
+pub fn claim_quest_step(chain_id: String) -> Result<(), JsValue> {
+    run(Command::ClaimQuestStep { chain_id })
+}
+
+// 执行玩家操作并记入操作日志，操作前按日志位置重新播种随机数
+fn execute(command: Command) -> Result<Outcome, GameError> {
+    let tick = FARM.with(|farm| farm.borrow().ticks);
+    let (seed, step) = ACTION_LOG.with(|log| {
+        let log = log.borrow();
+        (log.seed, (tick << 32) | log.next_step())
+    });
+    rng::reseed(seed, step);
+    let result = run_command(command.clone());
+    // 读档后撤销记录为空，失败的撤销不记入日志，避免回放时撤销了更早的操作
+    if command != Command::Undo || result.is_ok() {
+        ACTION_LOG.with(|log| log.borrow_mut().push(tick, command));
+        if ACTION_LOG.with(|log| log.borrow().is_full()) {
+            checkpoint_action_log();
         }
+    }
+    if let Err(err) = &result {
+        report_error(err);
+    }
+    result
+}
 
-        // 设置定时器
-        let _ = window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(
-            f_clone
-                .borrow()
-                .as_ref()
-                .unwrap()
-                .as_ref()
-                .unchecked_ref(),
-            1000,
-        );
-    }) as Box<dyn FnMut()>);
+// 日志达到上限时以当前状态作为检查点，撤销记录一并清空，使回放时的撤销与实际游戏一致
+fn checkpoint_action_log() {
+    UNDO.with(|history| history.borrow_mut().clear());
+    let state = serde_json::to_string(&game_state()).expect("游戏状态总能序列化");
+    ACTION_LOG.with(|log| log.borrow_mut().set_checkpoint(state));
+}
 
-    f.borrow_mut().replace(closure);
-    let _ = window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(
-        f.borrow().as_ref().unwrap().as_ref().unchecked_ref(),
-        1000,
-    );
+// 提示玩家操作失败的原因
+fn report_error(err: &GameError) {
+    if !crate::utils::is_muted() {
+        web_sys::console::warn_1(&err.message().into());
+    }
+    crate::utils::show_message(&err.message());
+    play_sound("buy_fail.wav");
+}
 
-    Ok(())
+// 把失败的原因转换为 { code, params } 交给页面脚本
+fn js_result<T>(result: Result<T, GameError>) -> Result<T, JsValue> {
+    result.map_err(|err| serde_wasm_bindgen::to_value(&err).unwrap_or_else(JsValue::from))
 }
 
-// 加载图片
-fn load_image(src: &str, setter: fn(HtmlImageElement)) -> Result<(), JsValue> {
-    let document = window().unwrap().document().unwrap();
-    let img = document.create_element("img")?.dyn_into::<HtmlImageElement>()?;
+// 执行没有返回值的玩家操作
+fn run(command: Command) -> Result<(), JsValue> {
+    js_result(execute(command).map(|_| ()))
+}
 
-    let img_clone = img.clone();
-    let closure = Closure::wrap(Box::new(move || {
-        setter(img_clone.clone());
-        LOADED_COUNT.with(|count| {
-            let mut count = count.borrow_mut();
-            *count += 1;
-            if *count == 10 {
-                start_render_loop().unwrap();
-            }
-        });
-    }) as Box<dyn FnMut()>);
+// 把操作分派给对应的处理函数
+fn run_command(command: Command) -> Result<Outcome, GameError> {
+    let done = |result: Result<(), GameError>| result.map(|_| Outcome::Done);
+    match command {
+        Command::ApplyBugProtection => {
+            bug_protection_action();
+            Ok(Outcome::Done)
+        }
+        Command::SprayTile { row, col } => done(spray_tile_action(row, col)),
+        Command::Plant { row, col, crop } => done(plant_action(row, col, crop)),
+        Command::Harvest { row, col } => done(harvest_action(row, col)),
+        Command::Fertilize { row, col, fertilizer } => done(fertilize_action(row, col, fertilizer)),
+        Command::SelectFertilizer { fertilizer } => done(select_fertilizer_action(fertilizer)),
+        Command::BuyFertilizer { fertilizer } => done(buy_fertilizer_action(fertilizer)),
+        Command::BuySeed { seed } => done(buy_seed_action(seed)),
+        Command::ClearTile { row, col } => done(clear_tile_action(row, col)),
+        Command::SellCrop { crop } => done(sell_crop_action(crop)),
+        Command::ExtractSeeds { crop } => done(extract_seeds_action(crop)),
+        Command::BuyMachine { machine } => done(buy_machine_action(machine)),
+        Command::PlaceMachine { row, col, machine } => done(place_machine_action(row, col, machine)),
+        Command::BuyStructure { structure } => done(buy_structure_action(structure)),
+        Command::PlaceStructure { row, col, structure } => done(place_structure_action(row, col, structure)),
+        Command::BuyPen { pen } => done(buy_pen_action(pen)),
+        Command::PlacePen { row, col, pen } => done(place_pen_action(row, col, pen)),
+        Command::BuyAnimal { row, col } => done(buy_animal_action(row, col)),
+        Command::FeedAnimals { row, col } => feed_animals_action(row, col).map(Outcome::Count),
+        Command::StartProcessing { row, col, input } => done(start_processing_action(row, col, input)),
+        Command::SellGood { good } => done(sell_good_action(good)),
+        Command::ClaimTaskReward { task_id } => done(claim_task_reward_action(task_id)),
+        Command::Undo => done(undo_action()),
+        Command::ClaimQuestStep { chain_id } => done(claim_quest_step_action(chain_id)),
+        Command::HarvestAll => Ok(Outcome::Area(harvest_all_action())),
+        Command::PlantRect { r0, c0, r1, c1, crop } => plant_rect_action(r0, c0, r1, c1, crop).map(Outcome::Area),
+        Command::FertilizeRect { r0, c0, r1, c1, fertilizer } => fertilize_rect_action(r0, c0, r1, c1, fertilizer).map(Outcome::Area),
+        Command::SprayAllInfested => Ok(Outcome::Area(spray_all_infested_action())),
+        Command::HireWorker { role, crop, r0, c0, r1, c1 } => {
+            done(hire_worker_action(role, crop, Area { r0, c0, r1, c1 }))
+        }
+        Command::FireWorker { worker_id } => done(fire_worker_action(worker_id)),
+        Command::AddRule { rule } => done(add_rule_action(rule)),
+        Command::RemoveRule { rule_id } => done(remove_rule_action(rule_id)),
+        Command::SetRuleEnabled { rule_id, enabled } => done(set_rule_enabled_action(rule_id, enabled)),
+    }
+}
 
-    img.set_onload(Some(closure.as_ref().unchecked_ref()));
-    img.set_src(src);
-    closure.forget();
+#[wasm_bindgen]
+// 执行序列化的操作，返回操作结果（成功为 1，喂食等返回数量），失败时返回 { code, params }
+pub fn execute_command(command: JsValue) -> Result<u32, JsValue> {
+    let command: Command = serde_wasm_bindgen::from_value(command)?;
+    js_result(execute(command).map(|outcome| outcome.count()))
+}
 
+// 切换界面语言，例如 "zh-CN" 或 "en-US"，选择会保存在浏览器中
+#[wasm_bindgen]
+pub fn set_locale(locale: String) -> Result<(), JsValue> {
+    let tag = locale;
+    let locale = Locale::from_tag(&tag).ok_or_else(|| JsValue::from_str(&tf!("error.unknown_locale", locale = tag)))?;
+    i18n::set_locale(locale);
+    let storage = window().unwrap().local_storage()?.unwrap();
+    storage.set_item("farm_game_locale", locale.tag())?;
     Ok(())
 }
 
-// 尝试出售作物
+// 获取当前界面语言
 #[wasm_bindgen]
-pub fn try_sell_crop(crop_type: String) -> bool {
-    let mut sold = false;
-    FARM.with(|farm_cell| {
-        let mut farm = farm_cell.borrow_mut();
-        if farm.inventory.remove_crop(&crop_type) {
-            SHOP.with(|shop_cell| {
-                shop_cell.borrow_mut().sell_crop(&crop_type);
-            });
-            sold = true;
-        }
-    });
-    if sold {
-        let _ = save_game();
-        play_sound("sell_crop.wav"); 
-    } else {
-        web_sys::console::log_1(&format!("Failed to sell {}: Not in inventory.", crop_type).into());
+pub fn get_locale() -> String {
+    i18n::locale().tag().to_string()
+}
+
+// 提供给界面的语言选项
+#[derive(Serialize)]
+struct LocaleOption {
+    tag: &'static str,
+    name: &'static str, // 该语言自己的名称，例如 English
+}
+
+// 获取所有可选的界面语言
+#[wasm_bindgen]
+pub fn get_locales() -> JsValue {
+    let options = Locale::ALL
+        .iter()
+        .map(|locale| LocaleOption {
+            tag: locale.tag(),
+            name: i18n::lookup(*locale, "locale.name").unwrap_or(locale.tag()),
+        })
+        .collect::<Vec<_>>();
+    serde_wasm_bindgen::to_value(&options).unwrap()
+}
+
+// 查找界面文字，params 为 { name: value } 形式的参数，供页面脚本使用
+#[wasm_bindgen]
+pub fn translate(key: String, params: JsValue) -> String {
+    let params: Option<std::collections::HashMap<String, serde_json::Value>> =
+        serde_wasm_bindgen::from_value(params).unwrap_or_default();
+    let params = params
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(text) => (name, text),
+            other => (name, other.to_string()),
+        })
+        .collect::<Vec<_>>();
+    let args = params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value as &dyn std::fmt::Display))
+        .collect::<Vec<_>>();
+    i18n::format(&key, &args)
+}
+
+// 读取保存的界面语言，没有保存过时按浏览器语言选择
+fn load_locale() {
+    let win = window().unwrap();
+    let saved = win
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item("farm_game_locale").ok().flatten());
+    let tag = saved.or_else(|| win.navigator().language());
+    if let Some(locale) = tag.as_deref().and_then(Locale::from_tag) {
+        i18n::set_locale(locale);
     }
-    sold
 }
 
 #[wasm_bindgen]
-// 获取任务
-pub fn get_tasks() -> JsValue {
-    TASKS.with(|tasks| {
-        serde_wasm_bindgen::to_value(&*tasks.borrow()).unwrap()
+// 获取操作日志
+pub fn get_action_log() -> JsValue {
+    let end_tick = FARM.with(|farm| farm.borrow().ticks);
+    ACTION_LOG.with(|log| {
+        let mut log = log.borrow().clone();
+        log.end_tick = end_tick;
+        serde_wasm_bindgen::to_value(&log).unwrap()
     })
 }
 
-// 生成新任务
-fn generate_new_task(last_id: u32) -> Task {
-    let crops = ["wheat", "corn", "carrot"];
-    let mut rng = rand::thread_rng();
-    let crop = crops.choose(&mut rng).unwrap().to_string();
-    let count = rng.gen_range(3..=15);
-    let reward = count * (10 + rng.gen_range(1..=5));
-    Task {
-        id: last_id + 1,
-        description: format!("种植{} {}个", match crop.as_str() {
-            "wheat" => "小麦",
-            "corn" => "玉米",
-            "carrot" => "胡萝卜",
-            _ => &crop,
-        }, count),
-        task_type: TaskType::PlantCrop { crop: crop.clone(), count },
-        progress: 0,
-        target: count,
-        reward,
-        completed: false,
-        claimed: false,
+#[wasm_bindgen]
+// 从新游戏开始回放操作日志，重建日志对应的游戏状态
+pub fn replay_action_log(log: JsValue) -> Result<(), JsValue> {
+    let log: ActionLog = serde_wasm_bindgen::from_value(log)?;
+    if !log.replayable {
+        return Err(JsValue::from_str(t("error.log_not_replayable")));
     }
+    replay(&log)?;
+    save_game()
 }
 
-#[wasm_bindgen]
-// 领取任务奖励
-pub fn claim_task_reward(task_id: u32) -> bool {
-    let mut claimed = false;
-    TASKS.with(|tasks| {
-        let mut tasks = tasks.borrow_mut();
-        if let Some(pos) = tasks.iter().position(|t| t.id == task_id) {
-            let task = &mut tasks[pos];
-            if task.completed && !task.claimed {
-                SHOP.with(|shop| shop.borrow_mut().balance += task.reward);
-                task.claimed = true;
-                claimed = true;
-                // 生成新任务并替换原任务
-                let new_id = tasks.iter().map(|t| t.id).max().unwrap_or(0);
-                let new_task = generate_new_task(new_id);
-                tasks[pos] = new_task;
-            }
+// 回放时不显示消息、不保存，也不计入成就
+fn replay(log: &ActionLog) -> Result<(), JsValue> {
+    let checkpoint = match &log.checkpoint {
+        Some(json) => Some(serde_json::from_str::<GameState>(json).map_err(|e| JsValue::from_str(&e.to_string()))?),
+        None => None,
+    };
+    REPLAYING.with(|flag| *flag.borrow_mut() = true);
+    crate::utils::set_muted(true);
+    reset_game(log.seed);
+    if let Some(state) = checkpoint {
+        apply_game_state(state);
+        ACTION_LOG.with(|current| *current.borrow_mut() = log.restart());
+    }
+    for entry in &log.entries {
+        while FARM.with(|farm| farm.borrow().ticks) < entry.tick {
+            tick();
         }
-    });
-    if claimed {
-        let _ = save_game();
-        play_sound("sell_crop.wav");
+        let _ = execute(entry.command.clone());
     }
-    claimed
+    while FARM.with(|farm| farm.borrow().ticks) < log.end_tick {
+        tick();
+    }
+    crate::utils::set_muted(false);
+    REPLAYING.with(|flag| *flag.borrow_mut() = false);
+    Ok(())
 }
 
 #[wasm_bindgen(start)]
 // 开始游戏
 pub fn start() -> Result<(), JsValue> {
-    let _ = load_game();
+    load_locale();
+    if let Err(err) = load_game() {
+        report_load_failure("farm_game_state", &err, "message.load_failed");
+    }
+    if let Err(err) = load_achievements() {
+        report_load_failure("farm_game_achievements", &err, "message.load_failed");
+    }
 
     // 播放背景音乐
     play_background_music();
 
     // 获取窗口和文档
-    let win = window().ok_or_else(|| JsValue::from_str("无法获取 window"))?;
-    let document = win.document().ok_or_else(|| JsValue::from_str("无法获取 document"))?;
+    let win = window().ok_or_else(|| JsValue::from_str(t("error.no_window")))?;
+    let document = win.document().ok_or_else(|| JsValue::from_str(t("error.no_document")))?;
 
     let canvas = document.get_element_by_id("canvas")
-        .ok_or_else(|| JsValue::from_str("找不到 canvas 元素"))?;
+        .ok_or_else(|| JsValue::from_str(&tf!("error.element_missing", id = "canvas")))?;
     let canvas: HtmlCanvasElement = canvas.dyn_into()?;
 
     let tooltip = document.create_element("div")?.dyn_into::<HtmlElement>()?;
@@ -983,7 +2417,7 @@ pub fn start() -> Result<(), JsValue> {
     
     // 修改 update_tooltip_content 函数
     fn update_tooltip_content(tooltip: &HtmlElement, row: usize, col: usize, x: i32, y: i32) {
-        if row >= 10 || col >= 10 {
+        let Some(tooltip_text) = tooltip_text(row, col) else {
             let _ = tooltip.set_attribute("style", r#"
                 position: fixed;
                 background: linear-gradient(145deg, rgba(20, 20, 40, 0.95), rgba(40, 40, 80, 0.95));
@@ -1004,13 +2438,6 @@ pub fn start() -> Result<(), JsValue> {
                 overflow-wrap: break-word;
             "#);
             return;
-        }
-
-        let crop_info = get_crop_info(row, col);
-        let tooltip_text = if crop_info.is_empty() {
-            format!("位置: ({}, {})\n状态: 空地\n点击种植作物", row, col)
-        } else {
-            crop_info  // 直接使用 crop_info，不再添加位置信息，因为 get_crop_info 已经包含了完整信息
         };
 
         // 使用 textContent 设置文本内容
@@ -1050,8 +2477,8 @@ pub fn start() -> Result<(), JsValue> {
         let canvas = canvas.clone();
         let tooltip = tooltip.clone();
         let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
-            let col = (event.offset_x() / size as i32) as usize;
-            let row = (event.offset_y() / size as i32) as usize;
+            let col = (event.offset_x() / size) as usize;
+            let row = (event.offset_y() / size) as usize;
             let x = event.client_x() + 10;
             let y = event.client_y() + 10;
 
@@ -1143,16 +2570,16 @@ pub fn start() -> Result<(), JsValue> {
         let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
             event.prevent_default();
             let size = 40;
-            let col = (event.offset_x() / size as i32) as usize;
-            let row = (event.offset_y() / size as i32) as usize;
+            let col = (event.offset_x() / size) as usize;
+            let row = (event.offset_y() / size) as usize;
             
             let result = fertilize(row, col);
             
-            if result {
-                let _ = save_game();
-                web_sys::console::log_1(&format!("成功施肥位置 ({}, {})", row, col).into());
+            if result.is_ok() {
+                autosave();
+                web_sys::console::log_1(&tf!("log.fertilized", row = row, col = col).into());
             } else {
-                web_sys::console::log_1(&format!("施肥失败位置 ({}, {})", row, col).into());
+                web_sys::console::log_1(&tf!("log.fertilize_failed", row = row, col = col).into());
             }
         }) as Box<dyn FnMut(_)>);
         canvas.add_event_listener_with_callback("contextmenu", closure.as_ref().unchecked_ref())?;
@@ -1162,7 +2589,7 @@ pub fn start() -> Result<(), JsValue> {
     // 打开背包
     {
         let bag_icon = document.get_element_by_id("bag-icon")
-            .ok_or_else(|| JsValue::from_str("找不到 bag-icon 元素"))?;
+            .ok_or_else(|| JsValue::from_str(&tf!("error.element_missing", id = "bag-icon")))?;
         let bag_icon: Element = bag_icon.dyn_into()?;
         let closure = Closure::wrap(Box::new(move |_event: MouseEvent| {
             play_sound("click.wav"); 
@@ -1270,25 +2697,23 @@ pub fn start() -> Result<(), JsValue> {
         let canvas = canvas.clone();
         let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
             let size = 40;
-            let col = (event.offset_x() / size as i32) as usize;
-            let row = (event.offset_y() / size as i32) as usize;
+            let col = (event.offset_x() / size) as usize;
+            let row = (event.offset_y() / size) as usize;
             
-            let state = get_state(row, col);
-            if state.starts_with("mature_") {
-                let crop_str = match state.as_str() {
-                    "mature_wheat" => "wheat",
-                    "mature_premium_wheat" => "premium_wheat",
-                    "mature_golden_wheat" => "golden_wheat",
-                    "mature_corn" => "corn",
-                    "mature_premium_corn" => "premium_corn",
-                    "mature_golden_corn" => "golden_corn",
-                    "mature_carrot" => "carrot",
-                    "mature_premium_carrot" => "premium_carrot",
-                    "mature_golden_carrot" => "golden_carrot",
-                    _ => "wheat", // 兜底
-                };
-                harvest(row, col);
-                web_sys::console::log_1(&format!("收获了位置 ({}, {})", row, col).into());
+            let (can_harvest, has_machine, has_pen) = FARM.with(|farm| {
+                farm.borrow()
+                    .tile(row, col)
+                    .map(|tile| (tile.can_harvest(), tile.machine().is_some(), tile.pen().is_some()))
+                    .unwrap_or((false, false, false))
+            });
+            if can_harvest {
+                if harvest(row, col).is_ok() {
+                    web_sys::console::log_1(&tf!("log.harvested", row = row, col = col).into());
+                }
+            } else if has_machine {
+                let _ = start_processing(row, col, None);
+            } else if has_pen {
+                let _ = feed_animals(row, col);
             }
         }) as Box<dyn FnMut(_)>);
         canvas.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
@@ -1320,19 +2745,31 @@ pub fn start() -> Result<(), JsValue> {
             let data_transfer = event.data_transfer().unwrap();
             let seed_type_string = data_transfer.get_data("text/plain").unwrap();
             
-            let col = (event.offset_x() / 40 as i32) as usize;
-            let row = (event.offset_y() / 40 as i32) as usize;
+            let col = (event.offset_x() / 40) as usize;
+            let row = (event.offset_y() / 40) as usize;
         
             // ✅ 新增：拖的是铲子 shovel，就清除作物
             if seed_type_string == "shovel" {
                 wasm_bindgen_futures::spawn_local(async move {
-                    clear_tile(row, col);
+                    let _ = clear_tile(row, col);
                 });
                 return;
             }
         
+            // 拖的是背包里的建筑，就放置到地块上
+            if let Some(building) = seed_type_string.strip_prefix("building:") {
+                if MachineType::from_id(building).is_some() {
+                    let _ = place_machine(row, col, building.to_string());
+                } else if AnimalType::from_pen_id(building).is_some() {
+                    let _ = place_pen(row, col, building.to_string());
+                } else {
+                    let _ = place_structure(row, col, building.to_string());
+                }
+                return;
+            }
+
             // 否则是种子，就种植
-            plant(row, col, seed_type_string);
+            let _ = plant(row, col, seed_type_string);
         }) as Box<dyn FnMut(_)>);
         
         canvas.add_event_listener_with_callback("drop", closure.as_ref().unchecked_ref())?;
@@ -1404,7 +2841,7 @@ pub fn start() -> Result<(), JsValue> {
             font-size: 14px;
             transition: all 0.2s ease;
         "#)?;
-        clear_button.set_text_content(Some("清空存档"));
+        clear_button.set_text_content(Some(t("ui.clear_save")));
 
         // 添加点击事件
         let closure = Closure::wrap(Box::new(move |_event: MouseEvent| {
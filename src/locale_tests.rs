@@ -0,0 +1,87 @@
+// 检查语言包：各语言的消息键和参数一致，游戏中的每个物品都有名称
+use crate::i18n::{self, Locale};
+use crate::item::ItemId;
+use std::collections::BTreeSet;
+
+// 消息中用到的 {name} 参数
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    text.split('{').skip(1).filter_map(|part| part.split_once('}').map(|(name, _)| name)).collect()
+}
+
+#[test]
+fn bundles_have_the_same_keys_and_placeholders() {
+    let source = i18n::bundle(Locale::ZhCn);
+    for locale in Locale::ALL {
+        let bundle = i18n::bundle(locale);
+        for (key, text) in source {
+            // 单数形式只在需要的语言中存在
+            if key.ends_with(".one") {
+                continue;
+            }
+            let translated = bundle.get(key).unwrap_or_else(|| panic!("{} 缺少 {}", locale.tag(), key));
+            assert_eq!(placeholders(text), placeholders(translated), "{} 的 {} 参数不一致", locale.tag(), key);
+        }
+        for (key, text) in bundle {
+            // 单数形式可以省略数量参数，例如 "Fertilize a plot"
+            let base = key.strip_suffix(".one").unwrap_or(key);
+            let original = source.get(base).unwrap_or_else(|| panic!("{} 多出 {}", locale.tag(), key));
+            assert!(placeholders(text).is_subset(&placeholders(original)), "{} 的 {} 参数不一致", locale.tag(), key);
+        }
+    }
+}
+
+#[test]
+fn every_catalog_entry_has_a_name() {
+    let mut ids: Vec<&str> = crate::shop::SHOP_SECTIONS.iter().flat_map(|section| section.items.iter().copied()).collect();
+    ids.extend(crate::processing::GOODS.iter().map(|good| good.id));
+    ids.extend(crate::animal::PRODUCTS.iter().map(|good| good.id));
+    ids.extend(crate::reward::ITEMS.iter().map(|item| item.id));
+
+    let mut keys: Vec<String> = crate::shop::SHOP_SECTIONS.iter().map(|section| section.title.to_string()).collect();
+    keys.extend(crate::achievement::ACHIEVEMENTS.iter().flat_map(|a| {
+        [format!("achievement.{}.name", a.id), format!("achievement.{}.description", a.id)]
+    }));
+    keys.extend(crate::quest::QUEST_CHAINS.iter().map(|chain| format!("quest.{}.name", chain.id)));
+
+    for locale in Locale::ALL {
+        i18n::set_locale(locale);
+        for id in &ids {
+            let item = ItemId::parse(id).unwrap_or_else(|_| panic!("未知物品 {}", id));
+            assert!(!item.display_name().contains('.'), "{} 缺少 {} 的名称", locale.tag(), id);
+        }
+        for key in &keys {
+            assert!(i18n::lookup(locale, key).is_some(), "{} 缺少 {}", locale.tag(), key);
+        }
+    }
+    i18n::set_locale(Locale::ZhCn);
+}
+
+#[test]
+fn formatting_follows_the_locale() {
+    i18n::set_locale(Locale::EnUs);
+    assert_eq!(i18n::number(1234567u32), "1,234,567");
+    assert_eq!(i18n::tf!("shop.harvests", count = 1), "1 harvest");
+    assert_eq!(i18n::tf!("shop.harvests", count = 3), "3 harvests");
+
+    i18n::set_locale(Locale::ZhCn);
+    assert_eq!(i18n::number(1234567u32), "1234567");
+    assert_eq!(i18n::tf!("shop.harvests", count = 1), "可收获1次");
+    assert!(Locale::from_tag("en-GB") == Some(Locale::EnUs));
+    assert!(Locale::from_tag("fr").is_none());
+}
+
+#[test]
+fn missing_keys_fall_back_to_the_key() {
+    let first = i18n::t("missing.key");
+    assert_eq!(first, "missing.key");
+    // 同一个键名只保留一份
+    assert!(std::ptr::eq(first, i18n::t("missing.key")));
+}
+
+#[test]
+fn unknown_ids_are_not_echoed_as_markup() {
+    let id = "<img src=x onerror=alert(1)>".repeat(4);
+    let message = crate::error::GameError::UnknownItem { id, kind: "crop" }.message();
+    assert!(!message.contains('<') && !message.contains('='));
+    assert!(message.len() < 100);
+}
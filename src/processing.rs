@@ -0,0 +1,188 @@
+use serde::{Serialize, Deserialize, Deserializer};
+use crate::tile::CropType;
+use crate::item::{self, ItemId};
+use crate::i18n::{self, tf};
+
+// 表示加工机器类型
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MachineType {
+    Mill,         // 磨坊：小麦 → 面粉
+    Juicer,       // 榨汁机：胡萝卜、水果 → 果汁
+    PopcornMaker, // 爆米花机：玉米 → 爆米花
+}
+
+// 表示一条加工配方
+pub struct Recipe {
+    pub machine: MachineType,
+    pub input: CropType,
+    pub input_count: u32,
+    pub output: &'static str,
+    pub output_count: u32,
+    pub ticks: u32,
+}
+
+// 表示一种加工品
+pub struct Good {
+    pub id: &'static str,
+    pub price: u32,
+}
+
+// 所有加工配方
+pub const RECIPES: &[Recipe] = &[
+    Recipe { machine: MachineType::Mill, input: CropType::Wheat, input_count: 2, output: "flour", output_count: 1, ticks: 20 },
+    Recipe { machine: MachineType::Mill, input: CropType::PremiumWheat, input_count: 2, output: "fine_flour", output_count: 1, ticks: 25 },
+    Recipe { machine: MachineType::Juicer, input: CropType::Carrot, input_count: 2, output: "carrot_juice", output_count: 1, ticks: 20 },
+    Recipe { machine: MachineType::Juicer, input: CropType::Strawberry, input_count: 3, output: "strawberry_juice", output_count: 1, ticks: 15 },
+    Recipe { machine: MachineType::Juicer, input: CropType::Apple, input_count: 2, output: "apple_juice", output_count: 1, ticks: 20 },
+    Recipe { machine: MachineType::PopcornMaker, input: CropType::Corn, input_count: 2, output: "popcorn", output_count: 1, ticks: 25 },
+    Recipe { machine: MachineType::PopcornMaker, input: CropType::PremiumCorn, input_count: 2, output: "caramel_popcorn", output_count: 1, ticks: 30 },
+];
+
+// 所有加工品及其售价
+pub const GOODS: &[Good] = &[
+    Good { id: "flour", price: 45 },
+    Good { id: "fine_flour", price: 85 },
+    Good { id: "carrot_juice", price: 60 },
+    Good { id: "strawberry_juice", price: 75 },
+    Good { id: "apple_juice", price: 85 },
+    Good { id: "popcorn", price: 70 },
+    Good { id: "caramel_popcorn", price: 120 },
+];
+
+impl Good {
+    pub fn display_name(&self) -> &'static str {
+        i18n::t(&format!("good.{}.name", self.id))
+    }
+}
+
+// 查找加工品，动物产品也视为加工品
+pub fn find_good(id: &str) -> Option<&'static Good> {
+    GOODS.iter().chain(crate::animal::PRODUCTS).find(|g| g.id == id)
+}
+
+impl MachineType {
+    pub fn id(&self) -> &'static str {
+        match self {
+            MachineType::Mill => "mill",
+            MachineType::Juicer => "juicer",
+            MachineType::PopcornMaker => "popcorn_maker",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<MachineType> {
+        match id {
+            "mill" => Some(MachineType::Mill),
+            "juicer" => Some(MachineType::Juicer),
+            "popcorn_maker" => Some(MachineType::PopcornMaker),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        i18n::t(&format!("machine.{}.name", self.id()))
+    }
+
+    // 商店中显示的加工说明
+    pub fn description(&self) -> &'static str {
+        i18n::t(&format!("machine.{}.description", self.id()))
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            MachineType::Mill => "⚙️",
+            MachineType::Juicer => "🧃",
+            MachineType::PopcornMaker => "🍿",
+        }
+    }
+
+    // 该机器可用的所有配方
+    pub fn recipes(&self) -> impl Iterator<Item = &'static Recipe> + '_ {
+        RECIPES.iter().filter(move |r| r.machine == *self)
+    }
+
+    pub fn find_recipe(&self, input: CropType) -> Option<&'static Recipe> {
+        self.recipes().find(|r| r.input == input)
+    }
+}
+
+// 表示一个正在进行的加工任务
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProcessingJob {
+    #[serde(deserialize_with = "deserialize_good")]
+    pub output: ItemId, // 加工品
+
+    pub output_count: u32,
+    pub remaining: u32,
+}
+
+// 读取存档中的加工品，未知编号或非加工品的编号会让存档读取失败
+fn deserialize_good<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ItemId, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    item::parse_good(&id).map(ItemId::Good).map_err(|e| serde::de::Error::custom(e.message()))
+}
+
+// 表示一台放置在地块上的加工机器
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Machine {
+    pub kind: MachineType,
+    pub job: Option<ProcessingJob>,
+}
+
+impl Machine {
+    pub fn new(kind: MachineType) -> Self {
+        Self { kind, job: None }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.job.is_some()
+    }
+
+    // 按配方开始加工，原料需由调用方从库存中扣除
+    pub fn start(&mut self, recipe: &Recipe) -> bool {
+        if self.is_busy() || recipe.machine != self.kind {
+            return false;
+        }
+        self.job = Some(ProcessingJob {
+            output: ItemId::Good(recipe.output),
+            output_count: recipe.output_count,
+            remaining: recipe.ticks,
+        });
+        true
+    }
+
+    // 推进加工，完成时返回产出的加工品及数量
+    pub fn tick(&mut self) -> Option<(ItemId, u32)> {
+        let job = self.job.as_mut()?;
+        job.remaining = job.remaining.saturating_sub(1);
+        if job.remaining == 0 {
+            let job = self.job.take()?;
+            return Some((job.output, job.output_count));
+        }
+        None
+    }
+
+    // 获取机器信息
+    pub fn get_info(&self) -> String {
+        let mut info = tf!("tooltip.machine", icon = self.kind.icon(), name = self.kind.display_name());
+        match &self.job {
+            Some(job) => {
+                info.push_str(&tf!("tooltip.machine.busy", name = job.output.display_name(), count = job.output_count, remaining = job.remaining));
+            }
+            None => info.push_str(i18n::t("tooltip.machine.idle")),
+        }
+        info.push_str(i18n::t("tooltip.machine.recipes"));
+        for recipe in self.kind.recipes() {
+            let input = recipe.input.display_name();
+            let output = find_good(recipe.output).map(|g| g.display_name()).unwrap_or(recipe.output);
+            info.push_str(&tf!(
+                "tooltip.machine.recipe",
+                input = input,
+                input_count = recipe.input_count,
+                output = output,
+                output_count = recipe.output_count,
+                ticks = recipe.ticks
+            ));
+        }
+        info
+    }
+}
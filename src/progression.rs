@@ -0,0 +1,86 @@
+use serde::Serialize;
+use crate::task::TaskEvent;
+
+// 每升一级开垦的土地数量
+pub const LAND_PER_LEVEL: u32 = 2;
+// 旧存档没有等级记录，视为该等级，保证原本可以购买的物品不被锁定
+pub const LEGACY_LEVEL: u32 = 7;
+
+// 达到每一级所需的累计经验，第 0 项对应 1 级
+pub const LEVEL_XP: &[u32] = &[0, 50, 150, 300, 500, 800, 1200, 1700, 2300, 3000];
+
+// 各等级解锁的商店物品
+pub const LEVEL_UNLOCKS: &[(u32, &[&str])] = &[
+    (2, &["premium_fertilizer", "scarecrow", "sprinkler"]),
+    (3, &["mill", "chicken_coop", "silo"]),
+    (4, &["premium_wheat", "premium_corn", "premium_carrot", "super_fertilizer", "juicer", "sheep_pen"]),
+    (5, &["strawberry", "beehive", "barn"]),
+    (6, &["apple", "popcorn_maker", "cow_barn"]),
+    (7, &["golden_wheat", "golden_corn", "golden_carrot"]),
+];
+
+pub fn max_level() -> u32 {
+    LEVEL_XP.len() as u32
+}
+
+pub fn legacy_level() -> u32 {
+    LEGACY_LEVEL
+}
+
+// 根据累计经验计算等级
+pub fn level_for_xp(xp: u32) -> u32 {
+    LEVEL_XP.iter().filter(|&&required| xp >= required).count() as u32
+}
+
+// 达到某一等级所需的累计经验
+pub fn xp_for_level(level: u32) -> u32 {
+    let index = level.clamp(1, max_level()) as usize - 1;
+    LEVEL_XP[index]
+}
+
+// 购买物品需要的等级，None 表示不受等级限制
+pub fn required_level(item: &str) -> Option<u32> {
+    LEVEL_UNLOCKS
+        .iter()
+        .find(|(_, items)| items.contains(&item))
+        .map(|(level, _)| *level)
+}
+
+// 玩家行为获得的经验
+pub fn xp_for_event(event: &TaskEvent) -> u32 {
+    match event {
+        TaskEvent::Planted(_) => 1,
+        // 收获的作物等级越高经验越多
+        TaskEvent::Harvested(crop) => 2 * (crop.tier() as u32 + 1),
+        TaskEvent::Sold(_) => 1,
+        TaskEvent::Earned(amount) => amount / 20,
+        TaskEvent::Fertilized => 1,
+        TaskEvent::PestCleared => 2,
+    }
+}
+
+// 提供给界面的等级信息
+#[derive(Serialize)]
+pub struct LevelInfo {
+    pub level: u32,
+    pub xp: u32,
+    pub level_xp: u32,              // 当前等级的起始经验
+    pub next_level_xp: Option<u32>, // 下一级所需的累计经验，满级时为 None
+    pub next_unlocks: Vec<String>,  // 下一级解锁的物品
+}
+
+pub fn level_info(level: u32, xp: u32) -> LevelInfo {
+    let next_level_xp = LEVEL_XP.get(level as usize).copied();
+    let next_unlocks = LEVEL_UNLOCKS
+        .iter()
+        .filter(|(l, _)| *l == level + 1)
+        .flat_map(|(_, items)| items.iter().map(|i| i.to_string()))
+        .collect();
+    LevelInfo {
+        level,
+        xp,
+        level_xp: xp_for_level(level),
+        next_level_xp,
+        next_unlocks,
+    }
+}
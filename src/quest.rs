@@ -0,0 +1,242 @@
+use serde::{Serialize, Deserialize};
+use crate::inventory::Inventory;
+use crate::task::{self, Task, TaskEvent, TaskType};
+use crate::tile::CropType;
+use crate::item::ItemId;
+use crate::i18n::t;
+
+// 表示任务链中一步的目标
+pub enum Objective {
+    Plant(CropType, u32),
+    Harvest(CropType, u32),
+    Sell(CropType, u32),
+    Earn(u32),
+    Fertilize(u32),
+    ClearPests(u32),
+    Own(ItemId, u32),
+    Balance(u32),
+}
+
+// 表示任务链中的一步
+pub struct QuestStep {
+    pub objective: Objective,
+    pub reward: u32,
+}
+
+// 表示一条任务链
+pub struct QuestChain {
+    pub id: &'static str,
+    pub prerequisites: &'static [&'static str], // 需要先完成的任务链
+    pub steps: &'static [QuestStep],
+    pub unlocks: &'static [&'static str],       // 完成后在商店解锁的物品
+}
+
+// 所有任务链
+pub const QUEST_CHAINS: &[QuestChain] = &[
+    QuestChain {
+        id: "tutorial",
+        prerequisites: &[],
+        steps: &[
+            QuestStep { objective: Objective::Plant(CropType::Wheat, 1), reward: 10 },
+            QuestStep { objective: Objective::Fertilize(1), reward: 15 },
+            QuestStep { objective: Objective::ClearPests(1), reward: 20 },
+            QuestStep { objective: Objective::Sell(CropType::Wheat, 1), reward: 15 },
+        ],
+        unlocks: &[],
+    },
+    QuestChain {
+        id: "premium_seeds",
+        prerequisites: &["tutorial"],
+        steps: &[
+            QuestStep { objective: Objective::Harvest(CropType::Wheat, 5), reward: 30 },
+            QuestStep { objective: Objective::Earn(200), reward: 50 },
+        ],
+        unlocks: &["premium_wheat", "premium_corn", "premium_carrot"],
+    },
+    QuestChain {
+        id: "orchard",
+        prerequisites: &["tutorial"],
+        steps: &[
+            QuestStep { objective: Objective::Harvest(CropType::Carrot, 5), reward: 30 },
+            QuestStep { objective: Objective::Own(ItemId::Crop(CropType::Corn), 5), reward: 40 },
+        ],
+        unlocks: &["strawberry", "apple"],
+    },
+    QuestChain {
+        id: "golden_seeds",
+        prerequisites: &["premium_seeds"],
+        steps: &[
+            QuestStep { objective: Objective::Plant(CropType::PremiumWheat, 3), reward: 40 },
+            QuestStep { objective: Objective::Harvest(CropType::PremiumCorn, 3), reward: 60 },
+            QuestStep { objective: Objective::Balance(800), reward: 100 },
+        ],
+        unlocks: &["golden_wheat", "golden_corn", "golden_carrot"],
+    },
+];
+
+impl Objective {
+    pub fn task_type(&self) -> TaskType {
+        match *self {
+            Objective::Plant(crop, count) => TaskType::PlantCrop { crop: ItemId::Crop(crop), count },
+            Objective::Harvest(crop, count) => TaskType::HarvestCrop { crop: ItemId::Crop(crop), count },
+            Objective::Sell(crop, count) => TaskType::SellCrop { crop: ItemId::Crop(crop), count },
+            Objective::Earn(amount) => TaskType::EarnCoins { amount },
+            Objective::Fertilize(count) => TaskType::FertilizeTiles { count },
+            Objective::ClearPests(count) => TaskType::ClearPests { count },
+            Objective::Own(item, count) => TaskType::OwnItem { item, count },
+            Objective::Balance(amount) => TaskType::ReachBalance { amount },
+        }
+    }
+}
+
+impl QuestChain {
+    pub fn name(&self) -> &'static str {
+        t(&format!("quest.{}.name", self.id))
+    }
+}
+
+pub fn find_chain(id: &str) -> Option<&'static QuestChain> {
+    QUEST_CHAINS.iter().find(|c| c.id == id)
+}
+
+// 判断物品是否需要通过任务链解锁
+pub fn is_quest_locked(item: &str) -> bool {
+    QUEST_CHAINS.iter().any(|c| c.unlocks.contains(&item))
+}
+
+// 生成任务链第 step 步对应的任务
+fn step_task(chain: &QuestChain, step: usize) -> Option<Task> {
+    chain
+        .steps
+        .get(step)
+        .map(|s| Task::new(step as u32 + 1, s.objective.task_type(), s.reward, None))
+}
+
+// 表示一条任务链的进度
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChainProgress {
+    pub id: String,
+    pub step: usize,
+    pub task: Option<Task>, // 当前步骤的任务，全部完成后为 None
+}
+
+// 表示领取任务链奖励的结果
+pub struct QuestClaim {
+    pub reward: u32,
+    pub finished_chain: Option<&'static QuestChain>, // 本次领取后完成的任务链
+}
+
+// 任务链进度记录
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct QuestLog {
+    pub chains: Vec<ChainProgress>,
+    pub completed: Vec<String>,
+}
+
+// 提供给界面的任务链信息
+#[derive(Serialize)]
+pub struct QuestView {
+    pub chain_id: String,
+    pub name: String,
+    pub step: usize,
+    pub total_steps: usize,
+    pub task: Task,
+    pub unlocks: Vec<String>,
+}
+
+impl QuestLog {
+    pub fn new() -> Self {
+        let mut log = Self::default();
+        log.start_available();
+        log
+    }
+
+    // 旧存档没有任务链记录，视为全部完成，避免已解锁的内容被重新锁定
+    pub fn all_completed() -> Self {
+        Self {
+            chains: Vec::new(),
+            completed: QUEST_CHAINS.iter().map(|c| c.id.to_string()).collect(),
+        }
+    }
+
+    fn is_completed(&self, id: &str) -> bool {
+        self.completed.iter().any(|c| c == id)
+    }
+
+    // 开启所有前置条件已满足的任务链
+    fn start_available(&mut self) {
+        for chain in QUEST_CHAINS {
+            let started = self.is_completed(chain.id) || self.chains.iter().any(|c| c.id == chain.id);
+            let ready = chain.prerequisites.iter().all(|p| self.is_completed(p));
+            if !started && ready {
+                self.chains.push(ChainProgress {
+                    id: chain.id.to_string(),
+                    step: 0,
+                    task: step_task(chain, 0),
+                });
+            }
+        }
+    }
+
+    // 物品是否已经可以在商店购买
+    pub fn is_unlocked(&self, item: &str) -> bool {
+        !is_quest_locked(item)
+            || QUEST_CHAINS
+                .iter()
+                .any(|c| c.unlocks.contains(&item) && self.is_completed(c.id))
+    }
+
+    pub fn record_event(&mut self, event: &TaskEvent) {
+        for progress in self.chains.iter_mut() {
+            if let Some(task) = progress.task.as_mut() {
+                task::record_event(std::slice::from_mut(task), event);
+            }
+        }
+    }
+
+    pub fn refresh_state(&mut self, inventory: &Inventory, balance: u32) {
+        for progress in self.chains.iter_mut() {
+            if let Some(task) = progress.task.as_mut() {
+                task::refresh_state(std::slice::from_mut(task), inventory, balance);
+            }
+        }
+    }
+
+    // 领取任务链当前步骤的奖励并进入下一步，步骤未完成时返回 None
+    pub fn claim(&mut self, chain_id: &str) -> Option<QuestClaim> {
+        let chain = find_chain(chain_id)?;
+        let pos = self.chains.iter().position(|c| c.id == chain_id)?;
+        let progress = &mut self.chains[pos];
+        let task = progress.task.as_ref().filter(|t| t.completed)?;
+        let reward = task.reward;
+
+        progress.step += 1;
+        progress.task = step_task(chain, progress.step);
+        if progress.task.is_some() {
+            return Some(QuestClaim { reward, finished_chain: None });
+        }
+
+        // 最后一步完成，记录任务链并开启后续任务链
+        self.chains.remove(pos);
+        self.completed.push(chain.id.to_string());
+        self.start_available();
+        Some(QuestClaim { reward, finished_chain: Some(chain) })
+    }
+
+    pub fn views(&self) -> Vec<QuestView> {
+        self.chains
+            .iter()
+            .filter_map(|progress| {
+                let chain = find_chain(&progress.id)?;
+                Some(QuestView {
+                    chain_id: chain.id.to_string(),
+                    name: chain.name().to_string(),
+                    step: progress.step,
+                    total_steps: chain.steps.len(),
+                    task: progress.task.as_ref()?.localized(),
+                    unlocks: chain.unlocks.iter().map(|u| u.to_string()).collect(),
+                })
+            })
+            .collect()
+    }
+}
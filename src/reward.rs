@@ -0,0 +1,159 @@
+use serde::{Serialize, Deserialize};
+use crate::farm::Farm;
+use crate::inventory::Pool;
+use crate::shop::Shop;
+use crate::tile::{CropType, FertilizerType};
+use crate::error::GameError;
+use crate::item::ItemId;
+use crate::i18n::{self, t, tf};
+
+// 土地已全部开垦时，每块土地奖励折算的金币
+pub const LAND_REFUND: u32 = 100;
+
+// 表示奖励物品的种类
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Tool,     // 工具
+    Cosmetic, // 装饰品
+}
+
+impl ItemKind {
+    pub fn label(&self) -> &'static str {
+        t(match self {
+            ItemKind::Tool => "item_kind.tool",
+            ItemKind::Cosmetic => "item_kind.cosmetic",
+        })
+    }
+}
+
+// 表示只能通过奖励获得的物品
+pub struct ItemDef {
+    pub id: &'static str,
+    pub icon: &'static str,
+    pub kind: ItemKind,
+}
+
+// 所有奖励物品
+pub const ITEMS: &[ItemDef] = &[
+    ItemDef { id: "watering_can", icon: "🚿", kind: ItemKind::Tool },
+    ItemDef { id: "golden_shovel", icon: "⛏️", kind: ItemKind::Tool },
+    ItemDef { id: "straw_hat", icon: "👒", kind: ItemKind::Cosmetic },
+    ItemDef { id: "farm_flag", icon: "🚩", kind: ItemKind::Cosmetic },
+    ItemDef { id: "trophy", icon: "🏆", kind: ItemKind::Cosmetic },
+];
+
+impl ItemDef {
+    pub fn display_name(&self) -> &'static str {
+        t(&format!("item.{}.name", self.id))
+    }
+}
+
+pub fn find_item(id: &str) -> Option<&'static ItemDef> {
+    ITEMS.iter().find(|i| i.id == id)
+}
+
+// 表示奖励包中的一项额外奖励，物品按编号保存，读取存档时即可发现未知物品
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum RewardItem {
+    Seed { id: CropType, count: u32 },
+    Fertilizer { id: FertilizerType, count: u32 },
+    Building { id: ItemId, count: u32 }, // 机器、建筑、装饰或围栏
+    Item { id: ItemId, count: u32 },     // 工具或装饰品
+    Land { plots: u32 },                 // 开垦未解锁的土地
+    Xp { amount: u32 },
+}
+
+impl RewardItem {
+    // 检查奖励物品的种类是否与奖励项一致
+    fn validate(&self) -> Result<(), GameError> {
+        match self {
+            RewardItem::Fertilizer { id: FertilizerType::None, .. } => Err(GameError::UnknownItem { id: "none".to_string(), kind: "fertilizer" }),
+            RewardItem::Building { id, .. } if !id.is_building() => Err(GameError::UnknownItem { id: id.id().to_string(), kind: "structure" }),
+            RewardItem::Item { id, .. } if !matches!(id, ItemId::Item(_)) => Err(GameError::UnknownItem { id: id.id().to_string(), kind: "item" }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            RewardItem::Seed { id, count } => tf!("reward.seed", name = tf!("item.seed_name", name = id.display_name()), count = count),
+            RewardItem::Fertilizer { id, count } => tf!("reward.fertilizer", name = id.display_name(), count = count),
+            RewardItem::Building { id, count } => {
+                let icon = match id {
+                    ItemId::Machine(machine) => machine.icon(),
+                    ItemId::Structure(structure) => structure.icon(),
+                    ItemId::Pen(animal) => animal.icon(),
+                    _ => "🏠",
+                };
+                tf!("reward.item", icon = icon, name = id.display_name(), count = count)
+            }
+            RewardItem::Item { id, count } => match find_item(id.id()) {
+                Some(item) => tf!("reward.item", icon = item.icon, name = item.display_name(), count = count),
+                None => tf!("reward.item_plain", name = id.display_name(), count = count),
+            },
+            RewardItem::Land { plots } => tf!("reward.land", count = plots),
+            RewardItem::Xp { amount } => tf!("reward.xp", amount = amount),
+        }
+    }
+}
+
+// 发放金币和奖励包，任何一项无效时不发放任何奖励，成功时返回奖励摘要
+pub fn grant(coins: u32, extras: &[RewardItem], farm: &mut Farm, shop: &mut Shop) -> Result<String, GameError> {
+    for item in extras {
+        item.validate()?;
+    }
+    let backpack_items: u32 = extras
+        .iter()
+        .map(|item| match item {
+            RewardItem::Seed { count, .. } | RewardItem::Fertilizer { count, .. } => *count,
+            _ => 0,
+        })
+        .sum();
+    if backpack_items > farm.inventory.free(Pool::Backpack) {
+        return Err(GameError::BackpackFull { needed: backpack_items });
+    }
+
+    let mut coins = coins;
+    let mut received = Vec::new();
+    for item in extras {
+        match item {
+            RewardItem::Seed { id, count } => {
+                for _ in 0..*count {
+                    farm.inventory.add_seed(*id);
+                }
+            }
+            RewardItem::Fertilizer { id, count } => {
+                for _ in 0..*count {
+                    farm.inventory.add_fertilizer(*id);
+                }
+            }
+            RewardItem::Building { id, count } => {
+                for _ in 0..*count {
+                    farm.inventory.add_building(*id);
+                }
+            }
+            RewardItem::Item { id, count } => farm.inventory.add_item(*id, *count),
+            RewardItem::Land { plots } => {
+                // 没有可开垦的土地时折算为金币
+                let unlocked = farm.unlock_plots(*plots);
+                let refund = (*plots - unlocked) * LAND_REFUND;
+                coins += refund;
+                if unlocked > 0 {
+                    received.push(RewardItem::Land { plots: unlocked }.describe());
+                }
+                continue;
+            }
+            RewardItem::Xp { amount } => {
+                if farm.add_xp(*amount) > 0 {
+                    received.push(tf!("reward.level_up", level = farm.level));
+                }
+            }
+        }
+        received.push(item.describe());
+    }
+    shop.balance += coins;
+    if coins > 0 {
+        received.insert(0, format!("💰 {}", i18n::number(coins)));
+    }
+    Ok(tf!("reward.received", rewards = i18n::join(&received)))
+}
@@ -0,0 +1,18 @@
+use std::cell::RefCell;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// 游戏逻辑使用的随机数生成器，按游戏种子和步骤重新播种，保证回放时结果一致
+thread_local! {
+    static GAME_RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(0));
+}
+
+// 按游戏种子和当前步骤重新播种
+pub fn reseed(seed: u64, step: u64) {
+    let mixed = seed ^ step.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    GAME_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(mixed));
+}
+
+pub fn with_rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    GAME_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
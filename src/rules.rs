@@ -0,0 +1,183 @@
+use serde::{Serialize, Deserialize};
+use crate::farm::{Farm, HarvestResult};
+use crate::inventory::Pool;
+use crate::item::ItemId;
+use crate::shop::Shop;
+use crate::tile::{CropType, TileState};
+use crate::error::GameError;
+use crate::i18n::tf;
+
+// 最多可以设置的规则数量
+pub const MAX_RULES: usize = 10;
+
+// 表示一条自动化规则
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum Rule {
+    // 指定作物成熟时收获，有种子时在原地重新种植
+    AutoReplant { crop: CropType },
+    // 库存中的作物超过阈值时出售多出的部分
+    SellAbove { crop: CropType, threshold: u32 },
+    // 种子少于阈值且金币高于下限时补货
+    BuySeedsBelow { seed: CropType, threshold: u32, amount: u32, min_balance: u32 },
+}
+
+impl Rule {
+    // 规则的文字说明
+    pub fn describe(&self) -> String {
+        match self {
+            Rule::AutoReplant { crop } => tf!("rule.auto_replant", crop = crop.display_name()),
+            Rule::SellAbove { crop, threshold } => {
+                tf!("rule.sell_above", crop = crop.display_name(), threshold = threshold)
+            }
+            Rule::BuySeedsBelow { seed, threshold, amount, min_balance } => tf!(
+                "rule.buy_seeds_below",
+                crop = seed.display_name(),
+                threshold = threshold,
+                min_balance = min_balance,
+                amount = amount
+            ),
+        }
+    }
+}
+
+// 规则执行产生的效果，由调用方记录任务和统计
+pub enum RuleEffect {
+    Harvested(HarvestResult),
+    Planted(CropType),
+    Sold { crop: CropType, revenue: u32 },
+    SeedBought { crop: CropType, cost: u32 },
+    Failed { rule_id: u32, error: GameError }, // 规则的操作没有成功
+}
+
+// 一条已保存的规则
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RuleEntry {
+    pub id: u32,
+    pub rule: Rule,
+    pub enabled: bool,
+    #[serde(default)]
+    pub failures: u32, // 执行失败的次数
+}
+
+// 玩家设置的所有规则，每次时间流逝按顺序执行
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RuleBook {
+    pub rules: Vec<RuleEntry>,
+    pub next_id: u32,
+}
+
+// 提供给界面的规则信息
+#[derive(Serialize)]
+pub struct RuleView {
+    pub id: u32,
+    pub description: String,
+    pub enabled: bool,
+    pub failures: u32,
+}
+
+impl RuleBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 添加规则，规则已满时返回 None
+    pub fn add(&mut self, rule: Rule) -> Option<u32> {
+        if self.rules.len() >= MAX_RULES {
+            return None;
+        }
+        self.next_id += 1;
+        self.rules.push(RuleEntry { id: self.next_id, rule, enabled: true, failures: 0 });
+        Some(self.next_id)
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.id != id);
+        self.rules.len() != before
+    }
+
+    pub fn set_enabled(&mut self, id: u32, enabled: bool) -> bool {
+        match self.rules.iter_mut().find(|r| r.id == id) {
+            Some(entry) => {
+                entry.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 执行所有启用的规则，unlocked 用于判断种子是否已解锁
+    pub fn run(&mut self, farm: &mut Farm, shop: &mut Shop, unlocked: impl Fn(&str) -> bool) -> Vec<RuleEffect> {
+        let mut effects = Vec::new();
+        for entry in self.rules.iter_mut().filter(|r| r.enabled) {
+            match entry.rule {
+                Rule::AutoReplant { crop } => replant(farm, crop, &mut effects),
+                Rule::SellAbove { crop, threshold } => {
+                    while farm.inventory.crop_count(crop) > threshold {
+                        let Some(freshness) = farm.inventory.take_crop(crop) else {
+                            break;
+                        };
+                        let revenue = shop.sell_crop(crop, freshness);
+                        effects.push(RuleEffect::Sold { crop, revenue });
+                    }
+                }
+                Rule::BuySeedsBelow { seed, threshold, amount, min_balance } => {
+                    let owned = farm.inventory.seeds.get(&ItemId::Crop(seed)).copied().unwrap_or(0);
+                    if owned >= threshold || !unlocked(seed.id()) {
+                        continue;
+                    }
+                    for _ in 0..amount {
+                        // 购买后金币不能低于玩家设置的下限
+                        let price = shop.get_seed_price(seed).unwrap_or(0);
+                        if shop.balance < min_balance.saturating_add(price) {
+                            break;
+                        }
+                        let bought = if farm.inventory.free(Pool::Backpack) == 0 {
+                            Err(GameError::BackpackFull { needed: 1 })
+                        } else {
+                            shop.buy_seed(seed)
+                        };
+                        if let Err(error) = bought {
+                            entry.failures += 1;
+                            effects.push(RuleEffect::Failed { rule_id: entry.id, error });
+                            break;
+                        }
+                        farm.inventory.add_seed(seed);
+                        effects.push(RuleEffect::SeedBought { crop: seed, cost: price });
+                    }
+                }
+            }
+        }
+        effects
+    }
+
+    pub fn views(&self) -> Vec<RuleView> {
+        self.rules
+            .iter()
+            .map(|r| RuleView {
+                id: r.id,
+                description: r.rule.describe(),
+                enabled: r.enabled,
+                failures: r.failures,
+            })
+            .collect()
+    }
+}
+
+// 收获所有成熟的指定作物，并尽量在原地补种
+fn replant(farm: &mut Farm, crop: CropType, effects: &mut Vec<RuleEffect>) {
+    for row in 0..farm.grid.len() {
+        for col in 0..farm.grid[0].len() {
+            let matches = matches!(farm.grid[row][col].state, TileState::Mature { crop: c, .. } if c == crop);
+            if !matches {
+                continue;
+            }
+            if let Ok(result) = farm.harvest(row, col) {
+                effects.push(RuleEffect::Harvested(result));
+            }
+            if farm.plant(row, col, crop).is_ok() {
+                effects.push(RuleEffect::Planted(crop));
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// 自动化规则的单元测试，直接在 Farm 和 Shop 上执行规则
+use crate::error::GameError;
+use crate::farm::Farm;
+use crate::inventory::BACKPACK_CAPACITY;
+use crate::item::ItemId;
+use crate::rules::{Rule, RuleBook, RuleEffect};
+use crate::shop::Shop;
+use crate::tile::{CropType, FertilizerType};
+
+fn setup(rule: Rule) -> (RuleBook, Farm, Shop) {
+    crate::utils::set_muted(true);
+    let mut rules = RuleBook::new();
+    assert!(rules.add(rule).is_some());
+    (rules, Farm::new(10, 10), Shop::new())
+}
+
+fn failures(effects: &[RuleEffect]) -> Vec<GameError> {
+    effects
+        .iter()
+        .filter_map(|effect| match effect {
+            RuleEffect::Failed { error, .. } => Some(error.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn buying_seeds_charges_the_shop_and_keeps_the_reserve() {
+    let (mut rules, mut farm, mut shop) = setup(Rule::BuySeedsBelow { seed: CropType::Wheat, threshold: 5, amount: 5, min_balance: 20 });
+    let price = shop.get_seed_price(CropType::Wheat).unwrap();
+    shop.balance = 20 + price * 3;
+
+    let effects = rules.run(&mut farm, &mut shop, |_| true);
+    assert!(farm.inventory.seeds.get(&ItemId::Crop(CropType::Wheat)) == Some(&3));
+    assert!(shop.balance == 20);
+    assert!(failures(&effects).is_empty());
+}
+
+#[test]
+fn failed_purchases_count_as_rule_failures() {
+    // 商店不再出售该种子
+    let (mut rules, mut farm, mut shop) = setup(Rule::BuySeedsBelow { seed: CropType::Wheat, threshold: 1, amount: 1, min_balance: 0 });
+    shop.balance = 1000;
+    shop.seeds.remove(&ItemId::Crop(CropType::Wheat));
+    let effects = rules.run(&mut farm, &mut shop, |_| true);
+    assert!(failures(&effects) == [GameError::NotForSale { item: "wheat".to_string() }]);
+    assert!(shop.balance == 1000 && rules.views()[0].failures == 1);
+
+    // 背包已满时不扣金币
+    let (mut rules, mut farm, mut shop) = setup(Rule::BuySeedsBelow { seed: CropType::Wheat, threshold: 1, amount: 1, min_balance: 0 });
+    shop.balance = 1000;
+    for _ in 0..BACKPACK_CAPACITY {
+        farm.inventory.add_fertilizer(FertilizerType::Basic);
+    }
+    let effects = rules.run(&mut farm, &mut shop, |_| true);
+    assert!(failures(&effects) == [GameError::BackpackFull { needed: 1 }]);
+    assert!(shop.balance == 1000 && rules.views()[0].failures == 1);
+}
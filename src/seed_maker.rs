@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+use crate::tile::CropType;
+
+// 制种机同时可以处理的作物数量
+pub const SEED_MAKER_SLOTS: usize = 3;
+
+// 表示一个制种任务
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SeedJob {
+    pub crop: CropType,
+    pub remaining: u32, // 剩余处理时间
+}
+
+// 制种机，把收获的作物加工回同类种子
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SeedMaker {
+    pub jobs: Vec<SeedJob>,
+}
+
+impl SeedMaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 一株作物制种所需的时间，为其首次生长时间的一半
+    pub fn processing_time(crop: CropType) -> u32 {
+        (crop.base_growth_time() / 2).max(1)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.jobs.len() >= SEED_MAKER_SLOTS
+    }
+
+    // 开始制种，如果制种机已满则返回 false
+    pub fn start(&mut self, crop: CropType) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.jobs.push(SeedJob {
+            crop,
+            remaining: Self::processing_time(crop),
+        });
+        true
+    }
+
+    // 推进所有制种任务，返回完成的作物及产出的种子数量
+    pub fn tick<R: Rng>(&mut self, rng: &mut R) -> Vec<(CropType, u32)> {
+        let mut finished = Vec::new();
+        for job in self.jobs.iter_mut() {
+            job.remaining = job.remaining.saturating_sub(1);
+            if job.remaining == 0 {
+                // 普通单季作物可产出 1~2 颗种子，高品级和多季作物只产出 1 颗
+                let count = if job.crop.tier() == 0 && !job.crop.is_perennial() {
+                    rng.gen_range(1..=2)
+                } else {
+                    1
+                };
+                finished.push((job.crop, count));
+            }
+        }
+        self.jobs.retain(|job| job.remaining > 0);
+        finished
+    }
+}
@@ -1,96 +1,187 @@
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
-use crate::utils::show_message;
+use crate::error::GameError;
+use crate::processing::{self, MachineType};
+use crate::item::ItemId;
+use crate::tile::{CropType, FertilizerType};
+use crate::structure::StructureType;
+use crate::animal::AnimalType;
+
+// 商店页面中的一个分区
+pub struct ShopSection {
+    pub title: &'static str,  // 标题的消息键
+    pub buy_fn: &'static str, // 点击购买时调用的导出函数
+    pub items: &'static [&'static str],
+}
+
+// 商店页面按顺序展示的商品
+pub const SHOP_SECTIONS: &[ShopSection] = &[
+    ShopSection { title: "shop.section.basic_seeds", buy_fn: "buy_seed", items: &["wheat", "corn", "carrot"] },
+    ShopSection { title: "shop.section.premium_seeds", buy_fn: "buy_seed", items: &["premium_wheat", "premium_corn", "premium_carrot"] },
+    ShopSection { title: "shop.section.golden_seeds", buy_fn: "buy_seed", items: &["golden_wheat", "golden_corn", "golden_carrot"] },
+    ShopSection { title: "shop.section.perennials", buy_fn: "buy_seed", items: &["strawberry", "apple"] },
+    ShopSection { title: "shop.section.machines", buy_fn: "buy_machine", items: &["mill", "juicer", "popcorn_maker"] },
+    ShopSection {
+        title: "shop.section.structures",
+        buy_fn: "buy_structure",
+        items: &["scarecrow", "sprinkler", "beehive", "silo", "barn", "path", "fence"],
+    },
+    ShopSection { title: "shop.section.pens", buy_fn: "buy_pen", items: &["chicken_coop", "cow_barn", "sheep_pen"] },
+    ShopSection {
+        title: "shop.section.fertilizers",
+        buy_fn: "buy_fertilizer",
+        items: &["basic_fertilizer", "premium_fertilizer", "super_fertilizer"],
+    },
+];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Shop {
-    pub seeds: HashMap<String, u32>,
-    pub fertilizers: HashMap<String, u32>,
+    pub seeds: HashMap<ItemId, u32>,
+    pub fertilizers: HashMap<ItemId, u32>,
+    #[serde(default)]
+    pub machines: HashMap<ItemId, u32>,
+    #[serde(default)]
+    pub structures: HashMap<ItemId, u32>,
+    #[serde(default)]
+    pub pens: HashMap<ItemId, u32>,
+    #[serde(default)]
+    pub animals: HashMap<AnimalType, u32>,
     pub balance: u32,
 }
 
 impl Shop {
     pub fn new() -> Self {
-        let mut seeds = HashMap::new();
-        seeds.insert("wheat".to_string(), 10);
-        seeds.insert("corn".to_string(), 20);
-        seeds.insert("carrot".to_string(), 15);
-        seeds.insert("premium_wheat".to_string(), 25);
-        seeds.insert("premium_corn".to_string(), 35);
-        seeds.insert("premium_carrot".to_string(), 30);
-        seeds.insert("golden_wheat".to_string(), 50);
-        seeds.insert("golden_corn".to_string(), 60);
-        seeds.insert("golden_carrot".to_string(), 55);
-
-        let mut fertilizers = HashMap::new();
-        fertilizers.insert("basic_fertilizer".to_string(), 25);
-        fertilizers.insert("premium_fertilizer".to_string(), 50);
-        fertilizers.insert("super_fertilizer".to_string(), 80);
-
-        Self { seeds, fertilizers, balance: 100 }
-    }
-
-    pub fn buy_fertilizer(&mut self, fertilizer_type: &str) -> bool {
-        if let Some(&price) = self.fertilizers.get(fertilizer_type) {
-            if self.balance >= price {
-                self.balance -= price;
-                return true;
-            } else {
-                show_message("金币不足，无法购买肥料！");
-            }
-        } else {
-            show_message("未找到该肥料！");
-        }
-        false
+        let seeds = HashMap::from([
+            (ItemId::Crop(CropType::Wheat), 10),
+            (ItemId::Crop(CropType::Corn), 20),
+            (ItemId::Crop(CropType::Carrot), 15),
+            (ItemId::Crop(CropType::PremiumWheat), 25),
+            (ItemId::Crop(CropType::PremiumCorn), 35),
+            (ItemId::Crop(CropType::PremiumCarrot), 30),
+            (ItemId::Crop(CropType::GoldenWheat), 50),
+            (ItemId::Crop(CropType::GoldenCorn), 60),
+            (ItemId::Crop(CropType::GoldenCarrot), 55),
+            (ItemId::Crop(CropType::Strawberry), 40),
+            (ItemId::Crop(CropType::Apple), 90),
+        ]);
+
+        let fertilizers = HashMap::from([
+            (ItemId::Fertilizer(FertilizerType::Basic), 25),
+            (ItemId::Fertilizer(FertilizerType::Premium), 50),
+            (ItemId::Fertilizer(FertilizerType::Super), 80),
+        ]);
+
+        let machines = HashMap::from([
+            (ItemId::Machine(MachineType::Mill), 150),
+            (ItemId::Machine(MachineType::Juicer), 200),
+            (ItemId::Machine(MachineType::PopcornMaker), 250),
+        ]);
+
+        let structures = HashMap::from([
+            (ItemId::Structure(StructureType::Scarecrow), 60),
+            (ItemId::Structure(StructureType::Sprinkler), 120),
+            (ItemId::Structure(StructureType::Beehive), 150),
+            (ItemId::Structure(StructureType::Silo), 300),
+            (ItemId::Structure(StructureType::Barn), 500),
+            (ItemId::Structure(StructureType::Path), 5),
+            (ItemId::Structure(StructureType::Fence), 10),
+        ]);
+
+        let pens = HashMap::from([
+            (ItemId::Pen(AnimalType::Chicken), 100),
+            (ItemId::Pen(AnimalType::Cow), 250),
+            (ItemId::Pen(AnimalType::Sheep), 180),
+        ]);
+
+        let animals = HashMap::from([
+            (AnimalType::Chicken, 40),
+            (AnimalType::Cow, 200),
+            (AnimalType::Sheep, 120),
+        ]);
+
+        Self { seeds, fertilizers, machines, structures, pens, animals, balance: 100 }
+    }
+
+    pub fn buy_fertilizer(&mut self, fertilizer: FertilizerType) -> Result<(), GameError> {
+        let price = self.get_fertilizer_price(fertilizer);
+        self.charge(price, fertilizer.id())
+    }
+
+
+    pub fn get_fertilizer_price(&self, fertilizer: FertilizerType) -> Option<u32> {
+        self.fertilizers.get(&ItemId::Fertilizer(fertilizer)).copied()
+    }
+
+    pub fn buy_seed(&mut self, seed: CropType) -> Result<(), GameError> {
+        let price = self.get_seed_price(seed);
+        self.charge(price, seed.id())
+    }
+
+    pub fn get_seed_price(&self, seed: CropType) -> Option<u32> {
+        self.seeds.get(&ItemId::Crop(seed)).copied()
     }
     
+    // 按新鲜度折算售价出售作物，返回实际收入
+    pub fn sell_crop(&mut self, crop: CropType, freshness: f32) -> u32 {
+        let revenue = (self.get_crop_price(crop) as f32 * freshness).round() as u32;
+        self.balance += revenue;
+        revenue
+    }
+
+    pub fn get_crop_price(&self, crop: CropType) -> u32 {
+        crop.sell_price()
+    }
+
+    // 商品的购买价格，种子、肥料、机器、建筑和围栏都在这里查找
+    pub fn list_price(&self, item: ItemId) -> Option<u32> {
+        [&self.seeds, &self.fertilizers, &self.machines, &self.structures, &self.pens]
+            .iter()
+            .find_map(|prices| prices.get(&item).copied())
+    }
+
+    pub fn buy_machine(&mut self, machine: MachineType) -> Result<(), GameError> {
+        let price = self.machines.get(&ItemId::Machine(machine)).copied();
+        self.charge(price, machine.id())
+    }
+
+    pub fn buy_structure(&mut self, structure: StructureType) -> Result<(), GameError> {
+        let price = self.structures.get(&ItemId::Structure(structure)).copied();
+        self.charge(price, structure.id())
+    }
+
+    pub fn buy_pen(&mut self, animal: AnimalType) -> Result<(), GameError> {
+        let price = self.pens.get(&ItemId::Pen(animal)).copied();
+        self.charge(price, animal.pen_id())
+    }
+
+    pub fn buy_animal(&mut self, animal: AnimalType) -> Result<(), GameError> {
+        let price = self.animals.get(&animal).copied();
+        self.charge(price, animal.id())
+    }
 
-    pub fn get_fertilizer_price(&self, fertilizer_type: &str) -> Option<u32> {
-        self.fertilizers.get(fertilizer_type).copied()
+    // 支付帮工工资
+    pub fn pay_wage(&mut self, wage: u32) -> Result<(), GameError> {
+        self.charge(Some(wage), "wage")
     }
 
-    pub fn buy_seed(&mut self, seed_type: &str) -> bool {
-        if let Some(&price) = self.seeds.get(seed_type) {
-            if self.balance >= price {
+    // 按价格扣除金币，商品不存在或金币不足时返回错误
+    fn charge(&mut self, price: Option<u32>, item: &str) -> Result<(), GameError> {
+        match price {
+            Some(price) if self.balance >= price => {
                 self.balance -= price;
-                return true;
-            } else {
-                show_message("金币不足，无法购买种子！");
+                Ok(())
             }
-        } else {
-            show_message("未找到该种子！");
+            Some(price) => Err(GameError::InsufficientFunds { price, balance: self.balance }),
+            None => Err(GameError::NotForSale { item: item.to_string() }),
         }
-        false
     }
-    
-    pub fn sell_crop(&mut self, crop_type: &str) {
-        let price = match crop_type {
-            "wheat" => 15,
-            "corn" => 25,
-            "carrot" => 20,
-            "premium_wheat" => 30,
-            "premium_corn" => 50,
-            "premium_carrot" => 40,
-            "golden_wheat" => 75,
-            "golden_corn" => 90,
-            "golden_carrot" => 80,
-            _ => 0,
-        };
-        self.balance += price;
-    }
-
-    pub fn get_crop_price(&self, crop_type: &str) -> Option<u32> {
-        match crop_type {
-            "wheat" => Some(15),
-            "corn" => Some(25),
-            "carrot" => Some(20),
-            "premium_wheat" => Some(30),
-            "premium_corn" => Some(50),
-            "premium_carrot" => Some(40),
-            "golden_wheat" => Some(75),
-            "golden_corn" => Some(90),
-            "golden_carrot" => Some(80),
-            _ => None,
-        }
+
+    pub fn sell_good(&mut self, good: &str) {
+        self.balance += self.get_good_price(good).unwrap_or(0);
+    }
+
+    pub fn get_good_price(&self, good: &str) -> Option<u32> {
+        processing::find_good(good).map(|g| g.price)
     }
 
     pub fn get_balance(&self) -> u32 {
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::tile::CropType;
+
+// 最多保留的按天统计数量，更早的数据只计入总计
+pub const MAX_TRACKED_DAYS: usize = 30;
+// 每分钟包含的时间流逝次数
+pub const TICKS_PER_MINUTE: u64 = 60;
+
+// 表示会被统计的经济行为
+pub enum StatEvent {
+    Planted(CropType),
+    Harvested(CropType),
+    Sold { crop: CropType, revenue: u32 },
+    SeedBought { crop: CropType, cost: u32 },
+    FertilizerUsed { crop: CropType, cost: u32 }, // 施肥成本计入地块上的作物
+    PestLoss(CropType),                           // 作物遭遇虫害
+    GoodSold { revenue: u32 },
+    Spent(u32),                                  // 购买建筑、动物等与作物无关的支出
+}
+
+// 单种作物的统计数据
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct CropStats {
+    pub planted: u32,
+    pub harvested: u32,
+    pub sold: u32,
+    pub revenue: u32,
+    pub seed_spend: u32,
+    pub fertilizer_spend: u32,
+    pub pest_losses: u32,
+    pub tile_ticks: u64, // 作物占用地块的累计时间
+}
+
+impl CropStats {
+    pub fn profit(&self) -> i64 {
+        self.revenue as i64 - self.seed_spend as i64 - self.fertilizer_spend as i64
+    }
+
+    // 每块地每分钟的利润
+    pub fn profit_per_tile_minute(&self) -> f64 {
+        if self.tile_ticks == 0 {
+            return 0.0;
+        }
+        self.profit() as f64 / (self.tile_ticks as f64 / TICKS_PER_MINUTE as f64)
+    }
+
+    // 与之前的统计相比增加的部分
+    fn difference(&self, before: &CropStats) -> CropStats {
+        CropStats {
+            planted: self.planted.saturating_sub(before.planted),
+            harvested: self.harvested.saturating_sub(before.harvested),
+            sold: self.sold.saturating_sub(before.sold),
+            revenue: self.revenue.saturating_sub(before.revenue),
+            seed_spend: self.seed_spend.saturating_sub(before.seed_spend),
+            fertilizer_spend: self.fertilizer_spend.saturating_sub(before.fertilizer_spend),
+            pest_losses: self.pest_losses.saturating_sub(before.pest_losses),
+            tile_ticks: self.tile_ticks.saturating_sub(before.tile_ticks),
+        }
+    }
+
+    fn subtract(&mut self, delta: &CropStats) {
+        self.planted = self.planted.saturating_sub(delta.planted);
+        self.harvested = self.harvested.saturating_sub(delta.harvested);
+        self.sold = self.sold.saturating_sub(delta.sold);
+        self.revenue = self.revenue.saturating_sub(delta.revenue);
+        self.seed_spend = self.seed_spend.saturating_sub(delta.seed_spend);
+        self.fertilizer_spend = self.fertilizer_spend.saturating_sub(delta.fertilizer_spend);
+        self.pest_losses = self.pest_losses.saturating_sub(delta.pest_losses);
+        self.tile_ticks = self.tile_ticks.saturating_sub(delta.tile_ticks);
+    }
+}
+
+// 一个游戏日的统计数据
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DayStats {
+    pub day: u32,
+    pub crops: BTreeMap<CropType, CropStats>,
+    pub goods_revenue: u32,
+    pub other_spend: u32,
+}
+
+impl DayStats {
+    fn apply(&mut self, event: &StatEvent) {
+        match *event {
+            StatEvent::Planted(crop) => self.crop(crop).planted += 1,
+            StatEvent::Harvested(crop) => self.crop(crop).harvested += 1,
+            StatEvent::Sold { crop, revenue } => {
+                let stats = self.crop(crop);
+                stats.sold += 1;
+                stats.revenue += revenue;
+            }
+            StatEvent::SeedBought { crop, cost } => self.crop(crop).seed_spend += cost,
+            StatEvent::FertilizerUsed { crop, cost } => self.crop(crop).fertilizer_spend += cost,
+            StatEvent::PestLoss(crop) => self.crop(crop).pest_losses += 1,
+            StatEvent::GoodSold { revenue } => self.goods_revenue += revenue,
+            StatEvent::Spent(cost) => self.other_spend += cost,
+        }
+    }
+
+    fn crop(&mut self, crop: CropType) -> &mut CropStats {
+        self.crops.entry(crop).or_default()
+    }
+
+    fn difference(&self, before: &DayStats) -> DayStats {
+        let empty = CropStats::default();
+        let crops = self
+            .crops
+            .iter()
+            .map(|(crop, stats)| (*crop, stats.difference(before.crops.get(crop).unwrap_or(&empty))))
+            .filter(|(_, stats)| *stats != empty)
+            .collect();
+        DayStats {
+            day: self.day,
+            crops,
+            goods_revenue: self.goods_revenue.saturating_sub(before.goods_revenue),
+            other_spend: self.other_spend.saturating_sub(before.other_spend),
+        }
+    }
+
+    // 扣除后没有任何数据的作物不再出现在统计中
+    fn subtract(&mut self, delta: &DayStats) {
+        for (crop, change) in &delta.crops {
+            if let Some(stats) = self.crops.get_mut(crop) {
+                stats.subtract(change);
+                if *stats == CropStats::default() {
+                    self.crops.remove(crop);
+                }
+            }
+        }
+        self.goods_revenue = self.goods_revenue.saturating_sub(delta.goods_revenue);
+        self.other_spend = self.other_spend.saturating_sub(delta.other_spend);
+    }
+
+    pub fn profit(&self) -> i64 {
+        self.crops.values().map(|c| c.profit()).sum::<i64>() + self.goods_revenue as i64 - self.other_spend as i64
+    }
+}
+
+// 整局游戏的统计数据
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Statistics {
+    pub days: Vec<DayStats>, // 最近的游戏日，按天数升序排列
+    pub totals: DayStats,    // 全部游戏日的总计，day 字段无意义
+}
+
+// 提供给界面的单种作物汇总
+#[derive(Serialize)]
+pub struct CropSummary {
+    pub crop: CropType,
+    pub display_name: String,
+    pub stats: CropStats,
+    pub profit: i64,
+    pub profit_per_tile_minute: f64,
+}
+
+// 提供给界面的统计报告
+#[derive(Serialize)]
+pub struct StatisticsReport {
+    pub days: Vec<DayStats>,
+    pub crops: Vec<CropSummary>,
+    pub goods_revenue: u32,
+    pub other_spend: u32,
+    pub total_profit: i64,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn day_mut(&mut self, day: u32) -> &mut DayStats {
+        if self.days.last().is_none_or(|d| d.day != day) {
+            self.days.push(DayStats { day, ..DayStats::default() });
+            if self.days.len() > MAX_TRACKED_DAYS {
+                self.days.remove(0);
+            }
+        }
+        self.days.last_mut().unwrap()
+    }
+
+    pub fn record(&mut self, day: u32, event: StatEvent) {
+        self.day_mut(day).apply(&event);
+        self.totals.apply(&event);
+    }
+
+    // 记录本次时间流逝中占用地块的作物
+    pub fn record_occupancy(&mut self, day: u32, crops: &[CropType]) {
+        for crop in crops {
+            self.day_mut(day).crop(*crop).tile_ticks += 1;
+            self.totals.crop(*crop).tile_ticks += 1;
+        }
+    }
+
+    // 与操作前的统计相比增加的部分，用于撤销操作
+    pub fn difference(&self, before: &Statistics) -> Statistics {
+        let days = self
+            .days
+            .iter()
+            .map(|day| match before.days.iter().find(|d| d.day == day.day) {
+                Some(old) => day.difference(old),
+                None => day.clone(),
+            })
+            .collect();
+        Statistics { days, totals: self.totals.difference(&before.totals) }
+    }
+
+    // 扣除撤销的操作带来的统计，之后记录的数据保持不变
+    pub fn subtract(&mut self, delta: &Statistics) {
+        for change in &delta.days {
+            if let Some(day) = self.days.iter_mut().find(|d| d.day == change.day) {
+                day.subtract(change);
+            }
+        }
+        self.totals.subtract(&delta.totals);
+    }
+
+    pub fn report(&self) -> StatisticsReport {
+        let crops = self
+            .totals
+            .crops
+            .iter()
+            .map(|(crop, stats)| CropSummary {
+                crop: *crop,
+                display_name: crop.display_name().to_string(),
+                stats: stats.clone(),
+                profit: stats.profit(),
+                profit_per_tile_minute: stats.profit_per_tile_minute(),
+            })
+            .collect();
+        StatisticsReport {
+            days: self.days.clone(),
+            crops,
+            goods_revenue: self.totals.goods_revenue,
+            other_spend: self.totals.other_spend,
+            total_profit: self.totals.profit(),
+        }
+    }
+}
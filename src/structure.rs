@@ -0,0 +1,100 @@
+use serde::{Serialize, Deserialize};
+use crate::i18n;
+
+// 表示可以放置在地块上的建筑和装饰
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StructureType {
+    Scarecrow, // 稻草人：降低周围作物的虫害概率
+    Sprinkler, // 洒水器：让周围作物保持湿润，生长更快
+    Beehive,   // 蜂箱：蜜蜂授粉，提高周围作物的杂交概率
+    Silo,      // 筒仓：占地 2x2，用于储存作物
+    Barn,      // 谷仓：占地 2x2，储存空间比筒仓更大
+    Path,      // 小路：装饰
+    Fence,     // 栅栏：装饰
+}
+
+impl StructureType {
+    pub fn id(&self) -> &'static str {
+        match self {
+            StructureType::Scarecrow => "scarecrow",
+            StructureType::Sprinkler => "sprinkler",
+            StructureType::Beehive => "beehive",
+            StructureType::Silo => "silo",
+            StructureType::Barn => "barn",
+            StructureType::Path => "path",
+            StructureType::Fence => "fence",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<StructureType> {
+        match id {
+            "scarecrow" => Some(StructureType::Scarecrow),
+            "sprinkler" => Some(StructureType::Sprinkler),
+            "beehive" => Some(StructureType::Beehive),
+            "silo" => Some(StructureType::Silo),
+            "barn" => Some(StructureType::Barn),
+            "path" => Some(StructureType::Path),
+            "fence" => Some(StructureType::Fence),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        i18n::t(&format!("structure.{}.name", self.id()))
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            StructureType::Scarecrow => "🎃",
+            StructureType::Sprinkler => "💦",
+            StructureType::Beehive => "🐝",
+            StructureType::Silo => "🛢️",
+            StructureType::Barn => "🏚️",
+            StructureType::Path => "🟫",
+            StructureType::Fence => "🚧",
+        }
+    }
+
+    // 占地大小（行数, 列数）
+    pub fn footprint(&self) -> (usize, usize) {
+        match self {
+            StructureType::Silo | StructureType::Barn => (2, 2),
+            _ => (1, 1),
+        }
+    }
+
+    // 影响范围半径，0 表示没有范围效果
+    pub fn effect_radius(&self) -> usize {
+        match self {
+            StructureType::Scarecrow => 2,
+            StructureType::Sprinkler => 1,
+            StructureType::Beehive => 2,
+            _ => 0,
+        }
+    }
+
+    // 增加的仓库容量
+    pub fn storage_bonus(&self) -> u32 {
+        match self {
+            StructureType::Silo => 100,
+            StructureType::Barn => 200,
+            _ => 0,
+        }
+    }
+
+    pub fn effect_description(&self) -> &'static str {
+        i18n::t(&format!("structure.{}.effect", self.id()))
+    }
+
+    // 商店中显示的简短说明
+    pub fn shop_description(&self) -> &'static str {
+        i18n::t(&format!("structure.{}.shop", self.id()))
+    }
+}
+
+// 虫害概率在稻草人范围内的倍率
+pub const SCARECROW_PEST_MULTIPLIER: f32 = 0.25;
+// 洒水器范围内作物每隔多少次时间流逝额外生长一次
+pub const SPRINKLER_BONUS_INTERVAL: u64 = 4;
+// 蜂箱范围内的杂交概率倍率
+pub const BEEHIVE_BREED_MULTIPLIER: f32 = 2.0;
@@ -0,0 +1,239 @@
+use serde::{Serialize, Deserialize};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::inventory::Inventory;
+use crate::tile::{CropType, FertilizerType};
+use crate::item::ItemId;
+use crate::reward::RewardItem;
+use crate::i18n::tf;
+
+// 表示任务类型
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum TaskType {
+    PlantCrop { crop: ItemId, count: u32 },
+    HarvestCrop { crop: ItemId, count: u32 },
+    SellCrop { crop: ItemId, count: u32 },
+    EarnCoins { amount: u32 },
+    FertilizeTiles { count: u32 },
+    ClearPests { count: u32 },
+    OwnItem { item: ItemId, count: u32 },
+    ReachBalance { amount: u32 },
+}
+
+// 表示任务，包含任务类型、进度、目标和奖励
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Task {
+    pub id: u32,
+    pub description: String,
+    pub task_type: TaskType,
+    pub progress: u32,
+    pub target: u32,
+    pub reward: u32,
+    pub completed: bool,
+    pub claimed: bool,
+    #[serde(default)]
+    pub deadline: Option<u32>, // 截止的游戏天数，None 表示不限时
+    #[serde(default)]
+    pub extras: Vec<RewardItem>, // 除金币以外的额外奖励
+}
+
+// 表示会推进任务进度的玩家行为
+pub enum TaskEvent {
+    Planted(CropType),
+    Harvested(CropType),
+    Sold(CropType),
+    Earned(u32),
+    Fertilized,
+    PestCleared,
+}
+
+impl TaskType {
+    // 任务目标数量
+    pub fn target(&self) -> u32 {
+        match self {
+            TaskType::PlantCrop { count, .. }
+            | TaskType::HarvestCrop { count, .. }
+            | TaskType::SellCrop { count, .. }
+            | TaskType::FertilizeTiles { count }
+            | TaskType::ClearPests { count }
+            | TaskType::OwnItem { count, .. } => *count,
+            TaskType::EarnCoins { amount } | TaskType::ReachBalance { amount } => *amount,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            TaskType::PlantCrop { crop, count } => tf!("task.plant_crop", crop = crop.display_name(), count = count),
+            TaskType::HarvestCrop { crop, count } => tf!("task.harvest_crop", crop = crop.display_name(), count = count),
+            TaskType::SellCrop { crop, count } => tf!("task.sell_crop", crop = crop.display_name(), count = count),
+            TaskType::EarnCoins { amount } => tf!("task.earn_coins", amount = crate::i18n::number(*amount)),
+            TaskType::FertilizeTiles { count } => tf!("task.fertilize_tiles", count = count),
+            TaskType::ClearPests { count } => tf!("task.clear_pests", count = count),
+            TaskType::OwnItem { item, count } => tf!("task.own_item", item = item.display_name(), count = count),
+            TaskType::ReachBalance { amount } => tf!("task.reach_balance", amount = crate::i18n::number(*amount)),
+        }
+    }
+
+    // 该行为为任务带来的进度增量
+    fn progress_for(&self, event: &TaskEvent) -> u32 {
+        match (self, event) {
+            (TaskType::PlantCrop { crop, .. }, TaskEvent::Planted(c))
+            | (TaskType::HarvestCrop { crop, .. }, TaskEvent::Harvested(c))
+            | (TaskType::SellCrop { crop, .. }, TaskEvent::Sold(c)) if *crop == ItemId::Crop(*c) => 1,
+            (TaskType::EarnCoins { .. }, TaskEvent::Earned(amount)) => *amount,
+            (TaskType::FertilizeTiles { .. }, TaskEvent::Fertilized) => 1,
+            (TaskType::ClearPests { .. }, TaskEvent::PestCleared) => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Task {
+    pub fn new(id: u32, task_type: TaskType, reward: u32, deadline: Option<u32>) -> Self {
+        Self {
+            id,
+            description: task_type.describe(),
+            target: task_type.target(),
+            task_type,
+            progress: 0,
+            reward,
+            completed: false,
+            claimed: false,
+            deadline,
+            extras: Vec::new(),
+        }
+    }
+
+    // 按当前语言重新生成任务说明，存档中保存的说明可能是切换语言之前的
+    pub fn localized(&self) -> Task {
+        Task { description: self.task_type.describe(), ..self.clone() }
+    }
+
+    // 为任务附加额外奖励
+    pub fn with_extras(mut self, extras: Vec<RewardItem>) -> Self {
+        self.extras = extras;
+        self
+    }
+
+    fn set_progress(&mut self, progress: u32) {
+        self.progress = progress.min(self.target);
+        if self.progress >= self.target {
+            self.completed = true;
+        }
+    }
+
+    // 撤销操作时扣回进度，重新判断是否完成
+    pub fn remove_progress(&mut self, amount: u32) {
+        self.progress = self.progress.saturating_sub(amount);
+        self.completed = self.progress >= self.target;
+    }
+}
+
+// 初始任务
+pub fn default_tasks() -> Vec<Task> {
+    vec![
+        Task::new(1, TaskType::PlantCrop { crop: ItemId::Crop(CropType::Wheat), count: 10 }, 30, None)
+            .with_extras(vec![RewardItem::Land { plots: 5 }, RewardItem::Xp { amount: 20 }]),
+        Task::new(2, TaskType::PlantCrop { crop: ItemId::Crop(CropType::Corn), count: 5 }, 20, None)
+            .with_extras(vec![RewardItem::Fertilizer { id: FertilizerType::Basic, count: 2 }]),
+        Task::new(3, TaskType::PlantCrop { crop: ItemId::Crop(CropType::Carrot), count: 3 }, 15, None)
+            .with_extras(vec![RewardItem::Item { id: ItemId::Item("straw_hat"), count: 1 }]),
+    ]
+}
+
+// 根据玩家行为推进任务进度
+pub fn record_event(tasks: &mut [Task], event: &TaskEvent) {
+    for task in tasks.iter_mut().filter(|t| !t.completed) {
+        let delta = task.task_type.progress_for(event);
+        if delta > 0 {
+            task.set_progress(task.progress + delta);
+        }
+    }
+}
+
+// 根据当前库存和余额刷新持有类任务的进度
+pub fn refresh_state(tasks: &mut [Task], inventory: &Inventory, balance: u32) {
+    for task in tasks.iter_mut().filter(|t| !t.completed) {
+        match &task.task_type {
+            TaskType::OwnItem { item, .. } => {
+                task.set_progress(inventory.count(*item));
+            }
+            TaskType::ReachBalance { .. } => task.set_progress(balance),
+            _ => {}
+        }
+    }
+}
+
+// 把已过期的未完成任务替换为新任务，返回被替换的数量
+pub fn replace_expired(tasks: &mut [Task], day: u32) -> u32 {
+    let mut replaced = 0;
+    for i in 0..tasks.len() {
+        let expired = matches!(tasks[i].deadline, Some(deadline) if day > deadline);
+        if expired && !tasks[i].completed {
+            let last_id = tasks.iter().map(|t| t.id).max().unwrap_or(0);
+            tasks[i] = generate_new_task(last_id, day);
+            replaced += 1;
+        }
+    }
+    replaced
+}
+
+// 生成新任务，部分任务带有截止日期并给予额外奖励
+pub fn generate_new_task(last_id: u32, day: u32) -> Task {
+    crate::rng::with_rng(|rng| generate_task_with(last_id, day, rng))
+}
+
+fn generate_task_with(last_id: u32, day: u32, rng: &mut impl Rng) -> Task {
+    let crops = [CropType::Wheat, CropType::Corn, CropType::Carrot];
+    let crop = ItemId::Crop(*crops.choose(rng).unwrap());
+    let count = rng.gen_range(3..=15);
+    let (task_type, mut reward) = match rng.gen_range(0..8) {
+        0 => (TaskType::PlantCrop { crop, count }, count * (10 + rng.gen_range(1..=5))),
+        1 => (TaskType::HarvestCrop { crop, count }, count * (12 + rng.gen_range(1..=5))),
+        2 => (TaskType::SellCrop { crop, count }, count * (8 + rng.gen_range(1..=5))),
+        3 => {
+            let amount = rng.gen_range(10..=50) * 10;
+            (TaskType::EarnCoins { amount }, amount / 4)
+        }
+        4 => {
+            let count = rng.gen_range(2..=6);
+            (TaskType::FertilizeTiles { count }, count * 20)
+        }
+        5 => {
+            let count = rng.gen_range(1..=4);
+            (TaskType::ClearPests { count }, count * 25)
+        }
+        6 => (TaskType::OwnItem { item: crop, count }, count * 6),
+        _ => {
+            let amount = rng.gen_range(3..=10) * 100;
+            (TaskType::ReachBalance { amount }, amount / 10)
+        }
+    };
+    // 一半的任务限时完成，奖励提高 50%
+    let deadline = if rng.gen_bool(0.5) {
+        reward += reward / 2;
+        Some(day + rng.gen_range(1..=3))
+    } else {
+        None
+    };
+    Task::new(last_id + 1, task_type, reward, deadline).with_extras(random_extras(rng))
+}
+
+// 随机生成额外奖励，每个任务都会获得经验
+fn random_extras(rng: &mut impl Rng) -> Vec<RewardItem> {
+    let mut extras = vec![RewardItem::Xp { amount: rng.gen_range(2..=6) * 5 }];
+    match rng.gen_range(0..10) {
+        0..=2 => {
+            let seed = *[CropType::Wheat, CropType::Corn, CropType::Carrot].choose(rng).unwrap();
+            extras.push(RewardItem::Seed { id: seed, count: rng.gen_range(2..=5) });
+        }
+        3 | 4 => extras.push(RewardItem::Fertilizer { id: FertilizerType::Basic, count: 1 }),
+        5 => extras.push(RewardItem::Land { plots: rng.gen_range(1..=3) }),
+        6 => {
+            let item = crate::reward::ITEMS.choose(rng).unwrap().id;
+            extras.push(RewardItem::Item { id: ItemId::Item(item), count: 1 });
+        }
+        _ => {}
+    }
+    extras
+}
@@ -1,8 +1,14 @@
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 use crate::utils::show_message;
+use crate::i18n::{self, tf};
+use crate::processing::Machine;
+use crate::structure::StructureType;
+use crate::animal::Pen;
+use crate::item::{self, ItemId};
 
-// 表示作物类型
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+// 表示作物类型，存档中按编号保存
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(into = "String")]
 pub enum CropType {
     Wheat,
     PremiumWheat,
@@ -13,10 +19,23 @@ pub enum CropType {
     Carrot,
     PremiumCarrot,
     GoldenCarrot,
+    Strawberry,  // 多季作物：草莓，收获后重新结果
+    Apple,       // 多季作物：苹果树，收获后重新结果
 }
 
-// 表示肥料类型
+// 表示作物科属，同科作物之间可以杂交育种
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CropFamily {
+    Wheat,
+    Corn,
+    Carrot,
+    Strawberry,
+    Apple,
+}
+
+// 表示肥料类型，存档中按编号保存
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(into = "String")]
 pub enum FertilizerType {
     None,
     Basic,
@@ -32,30 +51,193 @@ pub enum TileState {
         crop: CropType,
         timer: u32,
         fertilizer: FertilizerType,
+        #[serde(default)]
+        harvests: u32, // 已收获次数，多季作物用于判断是否还能再生
     },
-    Mature { crop: CropType },
-    Infested { crop: CropType }, // 🐛 新增虫害状态
+    Mature {
+        crop: CropType,
+        #[serde(default)]
+        harvests: u32,
+    },
+    Infested {
+        crop: CropType,
+        #[serde(default)]
+        harvests: u32,
+    }, // 🐛 新增虫害状态
+}
+
+impl TileState {
+    // 地块上的作物，空地返回 None
+    pub fn crop(&self) -> Option<CropType> {
+        match *self {
+            TileState::Empty => None,
+            TileState::Planted { crop, .. } | TileState::Mature { crop, .. } | TileState::Infested { crop, .. } => Some(crop),
+        }
+    }
+}
+
+// 表示地块上除作物以外的内容
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub enum TileContent {
+    #[default]
+    Soil,             // 耕地，可以种植作物
+    Machine(Machine), // 加工机器
+    Pen(Pen),         // 动物围栏
+    Structure {
+        kind: StructureType,
+        origin: (usize, usize), // 建筑左上角所在地块，多格建筑的每一格都指向它
+    },
+    Locked,           // 尚未开垦的土地
 }
 
 // 表示地块
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Tile {
     pub state: TileState,
+    #[serde(default)]
+    pub content: TileContent, // 地块内容：耕地、机器或建筑
+}
+
+// 地块的大类，界面据此选择图片和操作
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileStatus {
+    Locked,
+    Empty,
+    Planted,
+    Mature,
+    Infested,
+    Machine,
+    Structure,
+    Pen,
+}
+
+// 作用在地块上的区域效果
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileModifier {
+    Sprinkler, // 洒水器范围内，定期额外生长
+    Scarecrow, // 稻草人范围内，虫害概率降低
+    Beehive,   // 蜂箱范围内，杂交概率提高
+    BugNet,    // 捕虫网已部署，不会再出现虫害
+}
+
+// 渲染用的地块状态，作物和建筑以编号的形式发送给界面
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub struct TileSnapshot {
+    pub state: TileStatus,
+    pub crop: Option<CropType>,
+    pub building: Option<ItemId>, // 机器、建筑或围栏
+    pub fertilized: bool,
+    pub regrowing: bool,          // 多季作物收获后的再生阶段
+}
+
+// 地块的结构化信息，供界面自行排版，提示文字由 describe 生成
+#[derive(Clone, PartialEq, Serialize)]
+pub struct TileInfo {
+    pub state: TileStatus,
+    pub crop: Option<&'static str>,       // 作物编号
+    pub building: Option<&'static str>,   // 机器、建筑或围栏的编号
+    pub progress: f32,                    // 当前生长阶段的进度，0 到 1
+    pub remaining_seconds: u32,
+    pub total_seconds: u32,
+    pub harvests: u32,                    // 已收获次数
+    pub max_harvests: u32,
+    pub fertilizer: Option<&'static str>, // 肥料编号
+    pub expected_value: u32,              // 成熟后的出售价格
+    pub infested: bool,
+    pub pest_chance: f32,                 // 每次时间流逝出现虫害的概率
+    pub modifiers: Vec<TileModifier>,
 }
 
 // 实现作物类型方法
 impl CropType {
+    // 作物在库存和商店中使用的标识
+    pub fn id(&self) -> &'static str {
+        match self {
+            CropType::Wheat => "wheat",
+            CropType::PremiumWheat => "premium_wheat",
+            CropType::GoldenWheat => "golden_wheat",
+            CropType::Corn => "corn",
+            CropType::PremiumCorn => "premium_corn",
+            CropType::GoldenCorn => "golden_corn",
+            CropType::Carrot => "carrot",
+            CropType::PremiumCarrot => "premium_carrot",
+            CropType::GoldenCarrot => "golden_carrot",
+            CropType::Strawberry => "strawberry",
+            CropType::Apple => "apple",
+        }
+    }
+
+    // 根据标识解析作物，未知标识返回 None
+    pub fn from_id(id: &str) -> Option<CropType> {
+        match id {
+            "wheat" => Some(CropType::Wheat),
+            "premium_wheat" => Some(CropType::PremiumWheat),
+            "golden_wheat" => Some(CropType::GoldenWheat),
+            "corn" => Some(CropType::Corn),
+            "premium_corn" => Some(CropType::PremiumCorn),
+            "golden_corn" => Some(CropType::GoldenCorn),
+            "carrot" => Some(CropType::Carrot),
+            "premium_carrot" => Some(CropType::PremiumCarrot),
+            "golden_carrot" => Some(CropType::GoldenCarrot),
+            "strawberry" => Some(CropType::Strawberry),
+            "apple" => Some(CropType::Apple),
+            _ => None,
+        }
+    }
+
+    pub fn family(&self) -> CropFamily {
+        match self {
+            CropType::Wheat | CropType::PremiumWheat | CropType::GoldenWheat => CropFamily::Wheat,
+            CropType::Corn | CropType::PremiumCorn | CropType::GoldenCorn => CropFamily::Corn,
+            CropType::Carrot | CropType::PremiumCarrot | CropType::GoldenCarrot => CropFamily::Carrot,
+            CropType::Strawberry => CropFamily::Strawberry,
+            CropType::Apple => CropFamily::Apple,
+        }
+    }
+
+    // 品级：0 为普通，1 为优质，2 为金色
+    pub fn tier(&self) -> u8 {
+        match self {
+            CropType::PremiumWheat | CropType::PremiumCorn | CropType::PremiumCarrot => 1,
+            CropType::GoldenWheat | CropType::GoldenCorn | CropType::GoldenCarrot => 2,
+            _ => 0,
+        }
+    }
+
+    // 根据科属和品级查找作物，不存在该品级时返回 None
+    pub fn from_family_tier(family: CropFamily, tier: u8) -> Option<CropType> {
+        match (family, tier) {
+            (CropFamily::Wheat, 0) => Some(CropType::Wheat),
+            (CropFamily::Wheat, 1) => Some(CropType::PremiumWheat),
+            (CropFamily::Wheat, 2) => Some(CropType::GoldenWheat),
+            (CropFamily::Corn, 0) => Some(CropType::Corn),
+            (CropFamily::Corn, 1) => Some(CropType::PremiumCorn),
+            (CropFamily::Corn, 2) => Some(CropType::GoldenCorn),
+            (CropFamily::Carrot, 0) => Some(CropType::Carrot),
+            (CropFamily::Carrot, 1) => Some(CropType::PremiumCarrot),
+            (CropFamily::Carrot, 2) => Some(CropType::GoldenCarrot),
+            (CropFamily::Strawberry, 0) => Some(CropType::Strawberry),
+            (CropFamily::Apple, 0) => Some(CropType::Apple),
+            _ => None,
+        }
+    }
+
+    // 新鲜作物的出售价格，商店收购和地块信息都以这张表为准
     pub fn sell_price(&self) -> u32 {
         match self {
             CropType::Wheat => 15,
-            CropType::PremiumWheat => 25,
-            CropType::GoldenWheat => 50,
+            CropType::PremiumWheat => 30,
+            CropType::GoldenWheat => 75,
             CropType::Corn => 25,
-            CropType::PremiumCorn => 40,
-            CropType::GoldenCorn => 70,
+            CropType::PremiumCorn => 50,
+            CropType::GoldenCorn => 90,
             CropType::Carrot => 20,
-            CropType::PremiumCarrot => 32,
-            CropType::GoldenCarrot => 60,
+            CropType::PremiumCarrot => 40,
+            CropType::GoldenCarrot => 80,
+            CropType::Strawberry => 18,
+            CropType::Apple => 30,
         }
     }
 
@@ -70,11 +252,58 @@ impl CropType {
             CropType::Carrot => 12,
             CropType::PremiumCarrot => 16,
             CropType::GoldenCarrot => 24,
+            CropType::Strawberry => 18,
+            CropType::Apple => 40,
+        }
+    }
+
+    // 多季作物收获后再次结果所需的时间，单季作物与首次生长时间相同
+    pub fn regrowth_time(&self) -> u32 {
+        match self {
+            CropType::Strawberry => 6,
+            CropType::Apple => 12,
+            _ => self.base_growth_time(),
+        }
+    }
+
+    // 一次种植最多可以收获的次数，单季作物为 1
+    pub fn max_harvests(&self) -> u32 {
+        match self {
+            CropType::Strawberry => 4,
+            CropType::Apple => 6,
+            _ => 1,
         }
     }
 
+    // 收获后在仓库中保持新鲜的时间，过后只能以最低价出售
+    pub fn shelf_life(&self) -> u32 {
+        match self {
+            CropType::Strawberry => 120,
+            CropType::Carrot | CropType::PremiumCarrot | CropType::GoldenCarrot => 360,
+            CropType::Apple => 480,
+            CropType::Wheat | CropType::PremiumWheat | CropType::GoldenWheat => 600,
+            CropType::Corn | CropType::PremiumCorn | CropType::GoldenCorn => 480,
+        }
+    }
+
+    pub fn is_perennial(&self) -> bool {
+        self.max_harvests() > 1
+    }
+
     pub fn growth_time_with_fertilizer(&self, fertilizer: FertilizerType) -> u32 {
-        let base_time = self.base_growth_time();
+        Self::apply_fertilizer_to_time(self.base_growth_time(), fertilizer)
+    }
+
+    // 根据已收获次数计算当前阶段的生长时间，再生阶段使用更短的再生时间
+    pub fn growth_time(&self, fertilizer: FertilizerType, harvests: u32) -> u32 {
+        if harvests > 0 {
+            Self::apply_fertilizer_to_time(self.regrowth_time(), fertilizer)
+        } else {
+            self.growth_time_with_fertilizer(fertilizer)
+        }
+    }
+
+    fn apply_fertilizer_to_time(base_time: u32, fertilizer: FertilizerType) -> u32 {
         match fertilizer {
             FertilizerType::None => base_time,
             FertilizerType::Basic => (base_time as f32 * 0.8) as u32,
@@ -84,58 +313,53 @@ impl CropType {
     }
 
     pub fn display_name(&self) -> &'static str {
-        match self {
-            CropType::Wheat => "小麦",
-            CropType::PremiumWheat => "优质小麦",
-            CropType::GoldenWheat => "金色小麦",
-            CropType::Corn => "玉米",
-            CropType::PremiumCorn => "优质玉米",
-            CropType::GoldenCorn => "金色玉米",
-            CropType::Carrot => "胡萝卜",
-            CropType::PremiumCarrot => "优质胡萝卜",
-            CropType::GoldenCarrot => "金色胡萝卜",
-        }
+        i18n::t(&format!("crop.{}.name", self.id()))
     }
 
     pub fn description(&self) -> &'static str {
-        match self {
-            CropType::Wheat => "基础农作物，生长快速，用途广泛",
-            CropType::PremiumWheat => "优质小麦，产量更高，生长略慢",
-            CropType::GoldenWheat => "金色小麦，极高价值，生长周期长",
-            CropType::Corn => "高价值作物，生长较慢但收益丰厚",
-            CropType::PremiumCorn => "优质玉米，产量更高，生长更久",
-            CropType::GoldenCorn => "金色玉米，极高价值，生长周期最长",
-            CropType::Carrot => "营养丰富的根茎类作物，中等生长周期",
-            CropType::PremiumCarrot => "优质胡萝卜，产量更高，生长略慢",
-            CropType::GoldenCarrot => "金色胡萝卜，极高价值，生长周期长",
-        }
+        i18n::t(&format!("crop.{}.description", self.id()))
     }
 
     pub fn characteristics(&self) -> &'static str {
-        match self {
-            CropType::Wheat => "• 适应性强\n• 收获量稳定\n• 市场需求量大",
-            CropType::PremiumWheat => "• 更高产量\n• 稳定收益\n• 适合大面积种植",
-            CropType::GoldenWheat => "• 极高售价\n• 稀有作物\n• 需要耐心等待",
-            CropType::Corn => "• 营养价值高\n• 单株产量大\n• 储存时间长",
-            CropType::PremiumCorn => "• 更高产量\n• 高营养\n• 适合搭配肥料",
-            CropType::GoldenCorn => "• 极高售价\n• 稀有作物\n• 需要耐心等待",
-            CropType::Carrot => "• 富含维生素\n• 抗寒性好\n• 土壤要求低",
-            CropType::PremiumCarrot => "• 更高产量\n• 健康营养\n• 适合多地块轮作",
-            CropType::GoldenCarrot => "• 极高售价\n• 稀有作物\n• 需要耐心等待",
-        }
+        i18n::t(&format!("crop.{}.characteristics", self.id()))
     }
 
     pub fn planting_tips(&self) -> &'static str {
-        match self {
-            CropType::Wheat => "建议: 适合初学者种植，可大面积种植获得稳定收入",
-            CropType::PremiumWheat => "建议: 适合追求高产的玩家，注意生长周期",
-            CropType::GoldenWheat => "建议: 适合后期冲刺高收益，需耐心等待成熟",
-            CropType::Corn => "建议: 高价值作物，建议使用肥料缩短生长时间",
-            CropType::PremiumCorn => "建议: 适合搭配高级肥料，追求极致产出",
-            CropType::GoldenCorn => "建议: 适合后期冲刺高收益，需耐心等待成熟",
-            CropType::Carrot => "建议: 平衡型作物，适合搭配其他作物种植",
-            CropType::PremiumCarrot => "建议: 适合多样化种植，搭配轮作提升收益",
-            CropType::GoldenCarrot => "建议: 适合后期冲刺高收益，需耐心等待成熟",
+        i18n::t(&format!("crop.{}.tips", self.id()))
+    }
+
+    // 旧版存档按枚举名称保存作物
+    fn from_variant_name(name: &str) -> Option<CropType> {
+        match name {
+            "Wheat" => Some(CropType::Wheat),
+            "PremiumWheat" => Some(CropType::PremiumWheat),
+            "GoldenWheat" => Some(CropType::GoldenWheat),
+            "Corn" => Some(CropType::Corn),
+            "PremiumCorn" => Some(CropType::PremiumCorn),
+            "GoldenCorn" => Some(CropType::GoldenCorn),
+            "Carrot" => Some(CropType::Carrot),
+            "PremiumCarrot" => Some(CropType::PremiumCarrot),
+            "GoldenCarrot" => Some(CropType::GoldenCarrot),
+            "Strawberry" => Some(CropType::Strawberry),
+            "Apple" => Some(CropType::Apple),
+            _ => None,
+        }
+    }
+}
+
+impl From<CropType> for String {
+    fn from(crop: CropType) -> Self {
+        crop.id().to_string()
+    }
+}
+
+// 读取存档时未知的作物编号直接报错，而不是等到使用时才发现
+impl<'de> Deserialize<'de> for CropType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        match CropType::from_variant_name(&id) {
+            Some(crop) => Ok(crop),
+            None => item::parse_crop(&id).map_err(|e| serde::de::Error::custom(e.message())),
         }
     }
 }
@@ -151,30 +375,45 @@ impl FertilizerType {
         }
     }
 
-    pub fn display_name(&self) -> &'static str {
+    // 肥料在库存和商店中使用的标识
+    pub fn id(&self) -> &'static str {
         match self {
-            FertilizerType::None => "无",
-            FertilizerType::Basic => "基础肥料",
-            FertilizerType::Premium => "高级肥料",
-            FertilizerType::Super => "超级肥料",
+            FertilizerType::None => "none",
+            FertilizerType::Basic => "basic_fertilizer",
+            FertilizerType::Premium => "premium_fertilizer",
+            FertilizerType::Super => "super_fertilizer",
         }
     }
 
+    pub fn display_name(&self) -> &'static str {
+        i18n::t(&format!("fertilizer.{}.name", self.id()))
+    }
+
     pub fn speed_bonus_text(&self) -> &'static str {
-        match self {
-            FertilizerType::None => "",
-            FertilizerType::Basic => "(-20%时间)",
-            FertilizerType::Premium => "(-35%时间)",
-            FertilizerType::Super => "(-50%时间)",
-        }
+        i18n::t(&format!("fertilizer.{}.bonus", self.id()))
     }
 
     pub fn effect_description(&self) -> &'static str {
-        match self {
-            FertilizerType::None => "",
-            FertilizerType::Basic => "提供基础营养，轻微加速生长",
-            FertilizerType::Premium => "富含多种营养元素，显著促进生长",
-            FertilizerType::Super => "顶级营养配方，极大缩短生长周期",
+        i18n::t(&format!("fertilizer.{}.effect", self.id()))
+    }
+}
+
+impl From<FertilizerType> for String {
+    fn from(fertilizer: FertilizerType) -> Self {
+        fertilizer.id().to_string()
+    }
+}
+
+// 旧版存档按枚举名称保存肥料，未施肥的地块保存为 None
+impl<'de> Deserialize<'de> for FertilizerType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        match id.as_str() {
+            "none" | "None" => Ok(FertilizerType::None),
+            "Basic" => Ok(FertilizerType::Basic),
+            "Premium" => Ok(FertilizerType::Premium),
+            "Super" => Ok(FertilizerType::Super),
+            _ => item::parse_fertilizer(&id).map_err(|e| serde::de::Error::custom(e.message())),
         }
     }
 }
@@ -184,12 +423,51 @@ impl Tile {
     pub fn new() -> Self {
         Tile {
             state: TileState::Empty,
+            content: TileContent::Soil,
+        }
+    }
+
+    // 获取地块上的加工机器
+    pub fn machine(&self) -> Option<&Machine> {
+        match &self.content {
+            TileContent::Machine(machine) => Some(machine),
+            _ => None,
+        }
+    }
+
+    pub fn machine_mut(&mut self) -> Option<&mut Machine> {
+        match &mut self.content {
+            TileContent::Machine(machine) => Some(machine),
+            _ => None,
+        }
+    }
+
+    // 获取地块上的动物围栏
+    pub fn pen(&self) -> Option<&Pen> {
+        match &self.content {
+            TileContent::Pen(pen) => Some(pen),
+            _ => None,
+        }
+    }
+
+    pub fn pen_mut(&mut self) -> Option<&mut Pen> {
+        match &mut self.content {
+            TileContent::Pen(pen) => Some(pen),
+            _ => None,
+        }
+    }
+
+    // 获取地块上的建筑类型
+    pub fn structure(&self) -> Option<StructureType> {
+        match self.content {
+            TileContent::Structure { kind, .. } => Some(kind),
+            _ => None,
         }
     }
 
     // 判断地块是否可以种植
     pub fn can_plant(&self) -> bool {
-        matches!(self.state, TileState::Empty)
+        matches!(self.state, TileState::Empty) && matches!(self.content, TileContent::Soil)
     }
 
     // 判断地块是否可以收获
@@ -197,94 +475,207 @@ impl Tile {
         matches!(self.state, TileState::Mature { .. })
     }
 
-    // 判断地块是否可以施肥
-    pub fn can_fertilize(&self) -> bool {
-        matches!(self.state, TileState::Planted { fertilizer: FertilizerType::None, .. })
-    }
-
     // 获取地块信息
     pub fn get_crop_info(&self) -> String {
-        match self.state {
-            TileState::Empty => {
-                "🌱 空地\n━━━━━━━━━━━━━━\n状态: 可以种植作物\n操作: 拖拽种子到此处进行种植\n\n💡 小贴士:\n• 不同作物有不同的生长时间和收益\n• 使用肥料可以加速作物生长\n• 右键点击可以对已种植的作物施肥".to_string()
-            },
-            TileState::Planted { crop, timer, fertilizer } => {
-                let total_time = crop.growth_time_with_fertilizer(fertilizer);
-                let remaining = total_time.saturating_sub(timer);
-                let progress_percent = ((timer as f32 / total_time as f32) * 100.0) as u32;
-
-                let progress_bar_length = 20;
-                let filled_length = ((timer as f32 / total_time as f32) * progress_bar_length as f32) as usize;
-                let progress_bar = "█".repeat(filled_length) + "░".repeat(progress_bar_length - filled_length).as_str();
+        match &self.content {
+            TileContent::Machine(machine) => machine.get_info(),
+            TileContent::Pen(pen) => pen.get_info(),
+            _ => self.info().describe(),
+        }
+    }
 
-                let mut info = format!(
-                    "🌱 {} (生长中)\n━━━━━━━━━━━━━━\n📊 生长进度: {}% [{}]\n⏰ 剩余时间: {} 秒\n⏱️ 总生长时间: {} 秒",
-                    crop.display_name(), progress_percent, progress_bar, remaining, total_time
-                );
+    // 地块的结构化信息，区域效果和虫害概率由 Farm::tile_info 补充
+    pub fn info(&self) -> TileInfo {
+        let mut info = TileInfo {
+            state: self.status(),
+            crop: None,
+            building: self.building().map(|building| building.id()),
+            progress: 0.0,
+            remaining_seconds: 0,
+            total_seconds: 0,
+            harvests: 0,
+            max_harvests: 0,
+            fertilizer: None,
+            expected_value: 0,
+            infested: false,
+            pest_chance: 0.0,
+            modifiers: Vec::new(),
+        };
+        if !matches!(self.content, TileContent::Soil) {
+            return info;
+        }
+        if let Some(crop) = self.state.crop() {
+            info.crop = Some(crop.id());
+            info.max_harvests = crop.max_harvests();
+            info.expected_value = crop.sell_price();
+        }
+        match self.state {
+            TileState::Empty => {}
+            TileState::Planted { crop, timer, fertilizer, harvests } => {
+                let total_time = crop.growth_time(fertilizer, harvests);
+                info.progress = timer as f32 / total_time as f32;
+                info.remaining_seconds = total_time.saturating_sub(timer);
+                info.total_seconds = total_time;
+                info.harvests = harvests;
+                info.fertilizer = (fertilizer != FertilizerType::None).then(|| fertilizer.id());
+            }
+            TileState::Mature { harvests, .. } => {
+                info.progress = 1.0;
+                info.harvests = harvests;
+            }
+            TileState::Infested { harvests, .. } => {
+                info.harvests = harvests;
+                info.infested = true;
+            }
+        }
+        info
+    }
 
-                if fertilizer != FertilizerType::None {
-                    info.push_str(&format!(
-                        "\n🧪 肥料效果: {} {}\n💬 {}", 
-                        fertilizer.display_name(), 
-                        fertilizer.speed_bonus_text(),
-                        fertilizer.effect_description()
-                    ));
-                } else {
-                    info.push_str("\n🧪 肥料状态: 未施肥 (右键点击可施肥加速生长)");
-                }
+    // 地块的大类
+    pub fn status(&self) -> TileStatus {
+        match (&self.content, self.state) {
+            (TileContent::Machine(_), _) => TileStatus::Machine,
+            (TileContent::Pen(_), _) => TileStatus::Pen,
+            (TileContent::Structure { .. }, _) => TileStatus::Structure,
+            (TileContent::Locked, _) => TileStatus::Locked,
+            (TileContent::Soil, TileState::Empty) => TileStatus::Empty,
+            (TileContent::Soil, TileState::Planted { .. }) => TileStatus::Planted,
+            (TileContent::Soil, TileState::Mature { .. }) => TileStatus::Mature,
+            (TileContent::Soil, TileState::Infested { .. }) => TileStatus::Infested,
+        }
+    }
 
-                info.push_str(&format!(
-                    "\n\n📋 作物信息:\n📝 {}\n💰 预期收益: {} 金币\n\n🌟 作物特性:\n{}\n\n💡 {}",
-                    crop.description(),
-                    crop.sell_price(),
-                    crop.characteristics(),
-                    crop.planting_tips()
-                ));
+    // 地块上放置的机器、建筑或围栏
+    pub fn building(&self) -> Option<ItemId> {
+        match &self.content {
+            TileContent::Machine(machine) => Some(ItemId::Machine(machine.kind)),
+            TileContent::Pen(pen) => Some(ItemId::Pen(pen.kind)),
+            TileContent::Structure { kind, .. } => Some(ItemId::Structure(*kind)),
+            TileContent::Soil | TileContent::Locked => None,
+        }
+    }
 
-                info
-            },
-            TileState::Mature { crop } => {
-                format!(
-                    "✨ {} (成熟)\n━━━━━━━━━━━━━━\n🎉 状态: 可以收获！\n💰 收获价值: {} 金币\n👆 操作: 点击收获\n\n📋 作物信息:\n📝 {}\n\n🌟 作物特性:\n{}\n\n🏆 恭喜！这株作物已经完全成熟，可以获得丰厚的收益了！",
-                    crop.display_name(),
-                    crop.sell_price(),
-                    crop.description(),
-                    crop.characteristics()
-                )
-            },
-            TileState::Infested { crop } => {
-                format!(
-                    "🐛 {} (已被虫害感染)\n━━━━━━━━━━━━━━\n⚠️ 状态: 无法生长\n💀 需要喷雾驱虫恢复\n\n📋 作物信息:\n📝 {}\n💡 建议尽快清理虫害后继续生长",
-                    crop.display_name(),
-                    crop.description()
-                )
-            }
+    // 渲染一帧所需的地块状态
+    pub fn snapshot(&self) -> TileSnapshot {
+        let crop = match self.content {
+            TileContent::Soil => self.state.crop(),
+            _ => None,
+        };
+        let (fertilized, regrowing) = match self.state {
+            TileState::Planted { fertilizer, harvests, .. } => (fertilizer != FertilizerType::None, harvests > 0),
+            _ => (false, false),
+        };
+        TileSnapshot {
+            state: self.status(),
+            crop,
+            building: self.building(),
+            fertilized,
+            regrowing,
         }
     }
 
     // 施肥     
     pub fn apply_fertilizer(&mut self, fertilizer: FertilizerType) -> bool {
         match self.state {
-            TileState::Planted { crop, timer, fertilizer: FertilizerType::None } => {
+            TileState::Planted { crop, timer, fertilizer: FertilizerType::None, harvests } => {
                 self.state = TileState::Planted {
                     crop,
                     timer,
                     fertilizer,
+                    harvests,
                 };
-                show_message(&format!(
-                    "施肥成功！使用了{}，生长速度加快。",
-                    fertilizer.display_name()
-                ));
+                show_message(&tf!("message.fertilized", name = fertilizer.display_name()));
                 true
             },
             TileState::Infested { .. } => {
-                show_message("无法施肥：作物已被虫害感染！");
+                show_message(i18n::t("message.fertilize_infested"));
                 false
             },
             _ => {
-                show_message("无法施肥：该地块未种植或已施肥！");
+                show_message(i18n::t("message.fertilize_unavailable"));
                 false
             }
         }
     }
 }
+
+// 把地块信息排版成悬停提示文字
+impl TileInfo {
+    pub fn describe(&self) -> String {
+        let crop = self.crop.and_then(CropType::from_id);
+        match (self.state, crop) {
+            (TileStatus::Structure, _) => match self.building.and_then(StructureType::from_id) {
+                Some(kind) => tf!(
+                    "tooltip.structure",
+                    icon = kind.icon(),
+                    name = kind.display_name(),
+                    effect = kind.effect_description()
+                ),
+                None => String::new(),
+            },
+            (TileStatus::Locked, _) => i18n::t("tooltip.locked").to_string(),
+            (TileStatus::Planted, Some(crop)) => {
+                let progress_bar_length = 20;
+                let filled_length = ((self.progress * progress_bar_length as f32) as usize).min(progress_bar_length);
+                let progress_bar = "█".repeat(filled_length) + "░".repeat(progress_bar_length - filled_length).as_str();
+
+                let stage = if self.harvests > 0 { i18n::t("tooltip.stage.regrowing") } else { i18n::t("tooltip.stage.growing") };
+                let mut info = tf!(
+                    "tooltip.growing",
+                    name = crop.display_name(),
+                    stage = stage,
+                    progress = (self.progress * 100.0) as u32,
+                    bar = progress_bar,
+                    remaining = self.remaining_seconds,
+                    total = self.total_seconds
+                );
+
+                if crop.is_perennial() {
+                    info.push_str(&tf!("tooltip.harvested", count = self.harvests, max = self.max_harvests));
+                }
+
+                match self.fertilizer.map(FertilizerType::from_string) {
+                    Some(fertilizer) => info.push_str(&tf!(
+                        "tooltip.fertilizer",
+                        name = fertilizer.display_name(),
+                        bonus = fertilizer.speed_bonus_text(),
+                        effect = fertilizer.effect_description()
+                    )),
+                    None => info.push_str(i18n::t("tooltip.no_fertilizer")),
+                }
+
+                info.push_str(&tf!(
+                    "tooltip.growing_details",
+                    description = crop.description(),
+                    value = self.expected_value,
+                    characteristics = crop.characteristics(),
+                    tips = crop.planting_tips()
+                ));
+
+                info
+            }
+            (TileStatus::Mature, Some(crop)) => {
+                let mut info = tf!("tooltip.mature", name = crop.display_name(), value = self.expected_value);
+
+                if crop.is_perennial() {
+                    let remaining = self.max_harvests.saturating_sub(self.harvests + 1);
+                    info.push_str(&tf!("tooltip.regrows_left", count = remaining));
+                }
+
+                info.push_str(&tf!(
+                    "tooltip.mature_details",
+                    description = crop.description(),
+                    characteristics = crop.characteristics()
+                ));
+
+                info
+            }
+            (TileStatus::Infested, Some(crop)) => {
+                tf!("tooltip.infested", name = crop.display_name(), description = crop.description())
+            }
+            // 机器和围栏的说明依赖运行状态，由 Tile::get_crop_info 生成
+            (TileStatus::Machine | TileStatus::Pen, _) => String::new(),
+            _ => i18n::t("tooltip.empty").to_string(),
+        }
+    }
+}
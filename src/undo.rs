@@ -0,0 +1,356 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem::discriminant;
+use crate::achievement::{AchievementTracker, LifetimeStats};
+use crate::error::GameError;
+use crate::i18n::t;
+use crate::farm::Farm;
+use crate::inventory::{CropBatch, Inventory};
+use crate::item::ItemId;
+use crate::quest::QuestLog;
+use crate::statistics::Statistics;
+use crate::task::Task;
+use crate::tile::{TileContent, TileState};
+
+// 最多保留的可撤销操作数量
+pub const MAX_HISTORY: usize = 20;
+// 操作完成后允许撤销的时间流逝次数
+pub const UNDO_WINDOW_TICKS: u64 = 10;
+
+// 表示可以撤销的操作
+#[derive(Clone, Copy)]
+pub enum ActionKind {
+    Plant,
+    Harvest,
+    Clear,
+    Fertilize,
+    Buy,
+    Sell,
+}
+
+impl ActionKind {
+    pub fn display_name(&self) -> &'static str {
+        t(match self {
+            ActionKind::Plant => "action.plant",
+            ActionKind::Harvest => "action.harvest",
+            ActionKind::Clear => "action.clear",
+            ActionKind::Fertilize => "action.fertilize",
+            ActionKind::Buy => "action.buy",
+            ActionKind::Sell => "action.sell",
+        })
+    }
+}
+
+// 操作会影响的任务、经验和统计进度
+#[derive(Clone)]
+pub struct Progress {
+    pub tasks: Vec<Task>,
+    pub quests: QuestLog,
+    pub xp: u32,
+    pub level: u32,
+    pub statistics: Statistics,
+    pub achievements: AchievementTracker,
+}
+
+// 操作带来的任务、经验和统计进度变化，撤销时从当前进度中扣除，
+// 之后由时间流逝等其他途径获得的进度不受影响
+struct ProgressDelta {
+    tasks: Vec<(u32, u32)>,            // 任务编号及增加的进度
+    quests: Vec<(String, usize, u32)>, // 任务链、步骤及增加的进度
+    xp: u32,
+    statistics: Statistics,
+    lifetime: LifetimeStats,
+}
+
+impl ProgressDelta {
+    fn new(before: &Progress, after: &Progress) -> Self {
+        let tasks = after
+            .tasks
+            .iter()
+            .filter_map(|task| {
+                let old = before.tasks.iter().find(|t| t.id == task.id)?;
+                Some((task.id, task.progress.saturating_sub(old.progress)))
+            })
+            .filter(|(_, gained)| *gained > 0)
+            .collect();
+        let quests = after
+            .quests
+            .chains
+            .iter()
+            .filter_map(|chain| {
+                let old = before.quests.chains.iter().find(|c| c.id == chain.id && c.step == chain.step)?;
+                let gained = chain.task.as_ref()?.progress.saturating_sub(old.task.as_ref()?.progress);
+                Some((chain.id.clone(), chain.step, gained))
+            })
+            .filter(|(_, _, gained)| *gained > 0)
+            .collect();
+        ProgressDelta {
+            tasks,
+            quests,
+            xp: after.xp.saturating_sub(before.xp),
+            statistics: after.statistics.difference(&before.statistics),
+            lifetime: after.achievements.stats.difference(&before.achievements.stats),
+        }
+    }
+}
+
+// 操作开始前记录的状态
+pub struct Checkpoint {
+    pub tick: u64,
+    pub tile: Option<(usize, usize, TileState)>,
+    pub inventory: Inventory,
+    pub balance: u32,
+    pub progress: Progress,
+}
+
+// 一种物品的数量变化
+struct ItemDelta {
+    category: usize, // 对应 counts 中的位置
+    item: ItemId,
+    change: i64,
+}
+
+// 一条可撤销的操作记录
+pub struct UndoEntry {
+    pub kind: ActionKind,
+    tick: u64,
+    tile: Option<(usize, usize, TileState, TileState)>, // 地块坐标及操作前后的状态
+    items: Vec<ItemDelta>,
+    crops: Vec<CropDelta>,
+    balance_change: i64,
+    level: u32,
+    progress: ProgressDelta,
+}
+
+// 一个收获批次中作物的数量变化
+struct CropDelta {
+    crop: ItemId,
+    harvested_at: u64,
+    change: i64,
+    freshness: f32, // 撤销时重新放回的作物保持操作前的新鲜度
+}
+
+// 库存中除作物以外按数量计数的物品表，作物按收获批次单独计算
+fn counts(inventory: &Inventory) -> [&HashMap<ItemId, u32>; 5] {
+    [
+        &inventory.seeds,
+        &inventory.fertilizers,
+        &inventory.goods,
+        &inventory.buildings,
+        &inventory.items,
+    ]
+}
+
+fn counts_mut(inventory: &mut Inventory) -> [&mut HashMap<ItemId, u32>; 5] {
+    [
+        &mut inventory.seeds,
+        &mut inventory.fertilizers,
+        &mut inventory.goods,
+        &mut inventory.buildings,
+        &mut inventory.items,
+    ]
+}
+
+// 计算两份库存之间的数量变化
+fn inventory_delta(before: &Inventory, after: &Inventory) -> Vec<ItemDelta> {
+    let mut deltas = Vec::new();
+    for (category, (old, new)) in counts(before).into_iter().zip(counts(after)).enumerate() {
+        for item in old.keys().chain(new.keys().filter(|k| !old.contains_key(*k))) {
+            let change = new.get(item).copied().unwrap_or(0) as i64 - old.get(item).copied().unwrap_or(0) as i64;
+            if change != 0 {
+                deltas.push(ItemDelta { category, item: *item, change });
+            }
+        }
+    }
+    deltas
+}
+
+// 计算两份库存之间每个收获批次的作物数量变化
+fn crop_delta(before: &Inventory, after: &Inventory) -> Vec<CropDelta> {
+    let mut deltas = Vec::new();
+    let crops = before.crop_batches.keys().chain(after.crop_batches.keys().filter(|k| !before.crop_batches.contains_key(*k)));
+    for crop in crops {
+        let old = before.crop_batches.get(crop).map(Vec::as_slice).unwrap_or_default();
+        let new = after.crop_batches.get(crop).map(Vec::as_slice).unwrap_or_default();
+        let find = |batches: &[CropBatch], at: u64| batches.iter().find(|b| b.harvested_at == at).cloned();
+        for batch in old.iter().chain(new.iter().filter(|b| find(old, b.harvested_at).is_none())) {
+            let before = find(old, batch.harvested_at);
+            let after = find(new, batch.harvested_at);
+            let change = after.as_ref().map_or(0, |b| b.count as i64) - before.as_ref().map_or(0, |b| b.count as i64);
+            if change != 0 {
+                deltas.push(CropDelta {
+                    crop: *crop,
+                    harvested_at: batch.harvested_at,
+                    change,
+                    freshness: before.unwrap_or(batch.clone()).freshness,
+                });
+            }
+        }
+    }
+    deltas
+}
+
+impl Checkpoint {
+    // 根据操作完成后的状态和进度生成撤销记录
+    pub fn finish(self, kind: ActionKind, farm: &Farm, balance: u32, progress: &Progress) -> UndoEntry {
+        let tile = self
+            .tile
+            .map(|(row, col, before)| (row, col, before, farm.grid[row][col].state));
+        let items = inventory_delta(&self.inventory, &farm.inventory);
+        let crops = crop_delta(&self.inventory, &farm.inventory);
+        UndoEntry {
+            kind,
+            tick: self.tick,
+            tile,
+            items,
+            crops,
+            balance_change: balance as i64 - self.balance as i64,
+            level: self.progress.level,
+            progress: ProgressDelta::new(&self.progress, progress),
+        }
+    }
+}
+
+// 只恢复操作改变的作物、阶段和肥料，操作之后作物继续生长的进度保留
+fn revert_tile(current: TileState, before: TileState, after: TileState) -> TileState {
+    match (current, before, after) {
+        (TileState::Planted { timer, .. }, TileState::Planted { crop, fertilizer, harvests, .. }, TileState::Planted { .. }) => {
+            TileState::Planted { crop, timer, fertilizer, harvests }
+        }
+        _ => before,
+    }
+}
+
+impl UndoEntry {
+    // 检查操作是否还能撤销，不能撤销时返回原因
+    pub fn check(&self, farm: &Farm, balance: u32) -> Result<(), GameError> {
+        if farm.ticks > self.tick + UNDO_WINDOW_TICKS {
+            return Err(GameError::UndoExpired);
+        }
+        if farm.level != self.level {
+            return Err(GameError::UndoLevelChanged);
+        }
+        if let Some((row, col, _, after)) = &self.tile {
+            let tile = &farm.grid[*row][*col];
+            // 地块仍需处于操作后的阶段，作物可以继续生长
+            let unchanged = discriminant(&tile.state) == discriminant(after) && tile.state.crop() == after.crop();
+            if !unchanged || !matches!(tile.content, TileContent::Soil) {
+                return Err(GameError::UndoTileChanged);
+            }
+        }
+        let counts = counts(&farm.inventory);
+        for delta in &self.items {
+            let owned = counts[delta.category].get(&delta.item).copied().unwrap_or(0) as i64;
+            if owned < delta.change {
+                return Err(GameError::UndoItemsUsed);
+            }
+        }
+        for delta in &self.crops {
+            let owned = farm.inventory.crop_batches.get(&delta.crop)
+                .and_then(|batches| batches.iter().find(|b| b.harvested_at == delta.harvested_at))
+                .map_or(0, |b| b.count as i64);
+            if owned < delta.change {
+                return Err(GameError::UndoItemsUsed);
+            }
+        }
+        if (balance as i64) < self.balance_change {
+            return Err(GameError::InsufficientFunds { price: self.balance_change as u32, balance });
+        }
+        Ok(())
+    }
+
+    // 撤销操作，返回撤销后的金币余额，调用前需先通过 check
+    pub fn revert(&self, farm: &mut Farm, balance: u32) -> u32 {
+        if let Some((row, col, before, after)) = self.tile {
+            let tile = &mut farm.grid[row][col];
+            tile.state = revert_tile(tile.state, before, after);
+        }
+        let counts = counts_mut(&mut farm.inventory);
+        for delta in &self.items {
+            let count = counts[delta.category].entry(delta.item).or_insert(0);
+            *count = (*count as i64 - delta.change) as u32;
+            if *count == 0 {
+                counts[delta.category].remove(&delta.item);
+            }
+        }
+        // 按收获批次扣回或放回作物，其他批次不受影响
+        for delta in &self.crops {
+            let batches = farm.inventory.crop_batches.entry(delta.crop).or_default();
+            match batches.iter().position(|b| b.harvested_at >= delta.harvested_at) {
+                Some(i) if batches[i].harvested_at == delta.harvested_at => {
+                    batches[i].count = (batches[i].count as i64 - delta.change) as u32;
+                    if batches[i].count == 0 {
+                        batches.remove(i);
+                    }
+                }
+                position => batches.insert(
+                    position.unwrap_or(batches.len()),
+                    CropBatch { harvested_at: delta.harvested_at, count: (-delta.change) as u32, freshness: delta.freshness },
+                ),
+            }
+            if batches.is_empty() {
+                farm.inventory.crop_batches.remove(&delta.crop);
+            }
+        }
+        farm.xp = farm.xp.saturating_sub(self.progress.xp);
+        (balance as i64 - self.balance_change) as u32
+    }
+
+    // 以下方法从当前进度中扣除操作带来的进度，操作之后获得的进度保持不变
+    pub fn revert_tasks(&self, tasks: &mut [Task]) {
+        for (id, gained) in &self.progress.tasks {
+            // 已领取奖励的任务不再变化
+            if let Some(task) = tasks.iter_mut().find(|t| t.id == *id && !t.claimed) {
+                task.remove_progress(*gained);
+            }
+        }
+    }
+
+    pub fn revert_quests(&self, quests: &mut QuestLog) {
+        for (id, step, gained) in &self.progress.quests {
+            let chain = quests.chains.iter_mut().find(|c| c.id == *id && c.step == *step);
+            if let Some(task) = chain.and_then(|c| c.task.as_mut()) {
+                task.remove_progress(*gained);
+            }
+        }
+    }
+
+    pub fn revert_statistics(&self, statistics: &mut Statistics) {
+        statistics.subtract(&self.progress.statistics);
+    }
+
+    // 已解锁的成就保持解锁
+    pub fn revert_achievements(&self, achievements: &mut AchievementTracker) {
+        achievements.stats.subtract(&self.progress.lifetime);
+    }
+}
+
+// 最近操作的历史记录
+#[derive(Default)]
+pub struct UndoHistory {
+    entries: VecDeque<UndoEntry>,
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.entries.push_back(entry);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn last(&self) -> Option<&UndoEntry> {
+        self.entries.back()
+    }
+
+    pub fn pop(&mut self) -> Option<UndoEntry> {
+        self.entries.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -2,13 +2,30 @@ use wasm_bindgen::JsCast;
 use web_sys::window;
 use wasm_bindgen::closure::Closure;
 use web_sys::HtmlAudioElement;
+use std::cell::RefCell;
+
+thread_local! {
+    // 回放操作日志时不显示消息、不播放音效
+    static MUTED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+pub fn set_muted(muted: bool) {
+    MUTED.with(|m| *m.borrow_mut() = muted);
+}
+
+pub fn is_muted() -> bool {
+    MUTED.with(|m| *m.borrow())
+}
 
 // 显示消息
 pub fn show_message(msg: &str) {
+    if is_muted() {
+        return;
+    }
     if let Some(win) = window() {
         if let Some(doc) = win.document() {
             if let Some(box_div) = doc.get_element_by_id("message-box") {
-                box_div.set_inner_html(msg);
+                box_div.set_text_content(Some(msg));
                 box_div.set_attribute("style", "
                     position: fixed;
                     bottom: 100px;
@@ -53,6 +70,9 @@ pub fn play_background_music() {
 
 // 播放音效
 pub fn play_sound(file: &str) {
+    if is_muted() {
+        return;
+    }
     if let Ok(audio) = HtmlAudioElement::new_with_src(&format!("audio/{}", file)) {
         let _ = audio.play(); // 播放一次，不循环
     }
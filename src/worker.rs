@@ -0,0 +1,146 @@
+use serde::{Serialize, Deserialize};
+use crate::tile::CropType;
+use crate::i18n::t;
+
+// 最多可以雇佣的帮工数量
+pub const MAX_WORKERS: usize = 5;
+
+// 表示帮工的工种
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerRole {
+    Harvester,                  // 收获成熟的作物
+    Planter { crop: CropType }, // 在空地上种植指定作物
+    PestControl,                // 清除害虫
+}
+
+impl WorkerRole {
+    pub fn from_id(id: &str, crop: Option<&str>) -> Option<Self> {
+        match id {
+            "harvester" => Some(WorkerRole::Harvester),
+            "planter" => crop.and_then(CropType::from_id).map(|crop| WorkerRole::Planter { crop }),
+            "pest_control" => Some(WorkerRole::PestControl),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        t(match self {
+            WorkerRole::Harvester => "worker.harvester",
+            WorkerRole::Planter { .. } => "worker.planter",
+            WorkerRole::PestControl => "worker.pest_control",
+        })
+    }
+
+    // 每个游戏日的工资
+    pub fn daily_wage(&self) -> u32 {
+        match self {
+            WorkerRole::Harvester => 20,
+            WorkerRole::Planter { .. } => 15,
+            WorkerRole::PestControl => 25,
+        }
+    }
+}
+
+// 帮工负责的矩形区域，包含两个角
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Area {
+    pub r0: usize,
+    pub c0: usize,
+    pub r1: usize,
+    pub c1: usize,
+}
+
+// 表示一名帮工
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Worker {
+    pub id: u32,
+    pub role: WorkerRole,
+    pub area: Area,
+    pub paid: bool, // 今天的工资是否已支付，未支付时不工作
+    #[serde(default)]
+    pub idle: bool, // 支付工资后还没有处理过地块，工资顺延到下一天
+}
+
+// 帮工一次工作的结果
+pub enum WorkResult {
+    Harvested(crate::farm::HarvestResult),
+    Planted(CropType),
+    Sprayed,
+}
+
+// 农场雇佣的所有帮工
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Crew {
+    pub workers: Vec<Worker>,
+    pub next_id: u32,
+}
+
+// 提供给界面的帮工信息
+#[derive(Serialize)]
+pub struct WorkerView {
+    pub id: u32,
+    pub role: String,
+    pub crop: Option<String>,
+    pub area: Area,
+    pub daily_wage: u32,
+    pub paid: bool,
+}
+
+impl Crew {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.workers.len() >= MAX_WORKERS
+    }
+
+    // 雇佣帮工，雇佣时支付当天的工资，返回帮工编号
+    pub fn hire(&mut self, role: WorkerRole, area: Area) -> Option<u32> {
+        if self.is_full() {
+            return None;
+        }
+        self.next_id += 1;
+        self.workers.push(Worker { id: self.next_id, role, area, paid: true, idle: true });
+        Some(self.next_id)
+    }
+
+    // 新的一天每名帮工要支付的工资，已付工资却一整天没有处理地块的帮工不再收取
+    pub fn wages_due(&self) -> Vec<Option<u32>> {
+        self.workers
+            .iter()
+            .map(|w| if w.paid && w.idle { None } else { Some(w.role.daily_wage()) })
+            .collect()
+    }
+
+    // 记录每名帮工的工资是否付清，开始新的一天
+    pub fn start_day(&mut self, paid: &[bool]) {
+        for (worker, &paid) in self.workers.iter_mut().zip(paid) {
+            worker.paid = paid;
+            worker.idle = true;
+        }
+    }
+
+    pub fn fire(&mut self, id: u32) -> bool {
+        let before = self.workers.len();
+        self.workers.retain(|w| w.id != id);
+        self.workers.len() != before
+    }
+
+    pub fn views(&self) -> Vec<WorkerView> {
+        self.workers
+            .iter()
+            .map(|w| WorkerView {
+                id: w.id,
+                role: w.role.display_name().to_string(),
+                crop: match w.role {
+                    WorkerRole::Planter { crop } => Some(crop.id().to_string()),
+                    _ => None,
+                },
+                area: w.area,
+                daily_wage: w.role.daily_wage(),
+                paid: w.paid,
+            })
+            .collect()
+    }
+}